@@ -48,9 +48,7 @@ impl ArchVecs {
 
     /// Gets the list of values that have the specified architecture
     pub fn get_any(&self) -> Option<&ArchVec> {
-        self.vecs
-            .iter()
-            .find(|v| v.arch() == None)
+        self.vecs.iter().find(|v| v.arch().is_none())
     }
 
     /// Gets the list of values that have the specified architecture
@@ -80,9 +78,42 @@ impl ArchVecs {
 
     /// Gets the list of values that have no specific architecture
     pub fn any(&self) -> impl Iterator<Item = &str> {
-        self.get_any()
-            .map(|v| v.iter())
-            .unwrap_or_default()
+        self.get_any().map(|v| v.iter()).unwrap_or_default()
+    }
+
+    /// Adds a value to the given architecture, a None architecture adds the value to all
+    /// architectures
+    pub fn push<A: Into<String>, S: Into<String>>(&mut self, arch: Option<A>, value: S) {
+        let arch = arch.map(Into::into);
+        match self.vecs.iter_mut().find(|v| v.arch == arch) {
+            Some(vec) => vec.values.push(value.into()),
+            None => self
+                .vecs
+                .push(ArchVec::with_values(arch, vec![value.into()])),
+        }
+    }
+
+    /// Removes every occurrence of a value from the given architecture
+    ///
+    /// Returns true if anything was removed.
+    pub fn remove<A: AsRef<str>, S: AsRef<str>>(&mut self, arch: Option<A>, value: S) -> bool {
+        let len = self.all().count();
+        self.retain(arch, |v| v != value.as_ref());
+        len != self.all().count()
+    }
+
+    /// Only keeps the values of the given architecture that match the predicate
+    ///
+    /// If the architecture is left without values it is removed. Other architectures are not
+    /// touched, even when they are empty.
+    pub fn retain<A: AsRef<str>, F: FnMut(&str) -> bool>(&mut self, arch: Option<A>, mut f: F) {
+        let arch = arch.as_ref().map(|a| a.as_ref());
+        if let Some(i) = self.vecs.iter().position(|v| v.arch() == arch) {
+            self.vecs[i].values.retain(|v| f(v));
+            if self.vecs[i].values.is_empty() {
+                self.vecs.remove(i);
+            }
+        }
     }
 }
 
@@ -98,7 +129,10 @@ pub struct ArchVec {
 
 impl Default for &ArchVec {
     fn default() -> Self {
-        static EMPTY: ArchVec = ArchVec { arch: None, values: Vec::new() };
+        static EMPTY: ArchVec = ArchVec {
+            arch: None,
+            values: Vec::new(),
+        };
         &EMPTY
     }
 }
@@ -188,6 +222,137 @@ impl ArchVec {
     }
 }
 
+/// A borrowed version of [`ArchVecs`].
+///
+/// ArchVecsRef is produced when parsing a [`SrcinfoRef`](crate::SrcinfoRef) and borrows every
+/// architecture and value from the input.
+#[derive(Clone, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct ArchVecsRef<'a> {
+    /// A vector of each architecture and their values
+    pub(crate) vecs: Vec<ArchVecRef<'a>>,
+}
+
+impl<'a> Deref for ArchVecsRef<'a> {
+    type Target = [ArchVecRef<'a>];
+
+    fn deref(&self) -> &Self::Target {
+        &self.vecs
+    }
+}
+
+impl<'a, 'b> IntoIterator for &'b ArchVecsRef<'a> {
+    type IntoIter = std::slice::Iter<'b, ArchVecRef<'a>>;
+    type Item = &'b ArchVecRef<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.vecs.iter()
+    }
+}
+
+impl<'a> From<Vec<ArchVecRef<'a>>> for ArchVecsRef<'a> {
+    fn from(vecs: Vec<ArchVecRef<'a>>) -> Self {
+        ArchVecsRef { vecs }
+    }
+}
+
+impl<'a> ArchVecsRef<'a> {
+    /// Creates a new empty ArchVecsRef
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Gets the list of values that have no specific architecture
+    pub fn get_any(&self) -> Option<&ArchVecRef<'a>> {
+        self.vecs.iter().find(|v| v.arch().is_none())
+    }
+
+    /// Gets the list of values that have the specified architecture
+    pub fn get<S: AsRef<str>>(&self, arch: Option<S>) -> Option<&ArchVecRef<'a>> {
+        self.vecs
+            .iter()
+            .find(|v| v.arch() == arch.as_ref().map(|a| a.as_ref()))
+    }
+
+    /// Gets the list of values that apply to the given architecture
+    ///
+    /// The returned values with either belong to the given architecture or the None architecture.
+    pub fn arch<S: AsRef<str>>(&self, arch: S) -> impl Iterator<Item = &'a str> {
+        self.vecs
+            .iter()
+            .filter(move |v| v.supports(arch.as_ref()))
+            .flat_map(|v| v.iter())
+    }
+
+    /// Returns an iterator over all values in this ArchVecsRef
+    ///
+    /// You usually don't want this function as it means architecture specific fields are not being
+    /// handled.
+    pub fn all(&self) -> impl Iterator<Item = &'a str> {
+        self.vecs.iter().flat_map(|v| v.iter())
+    }
+
+    /// Gets the list of values that have no specific architecture
+    pub fn any(&self) -> impl Iterator<Item = &'a str> {
+        self.get_any().into_iter().flat_map(|v| v.iter())
+    }
+
+    /// Copies the values into an owned [`ArchVecs`]
+    pub fn to_owned(&self) -> ArchVecs {
+        ArchVecs {
+            vecs: self.vecs.iter().map(ArchVecRef::to_owned).collect(),
+        }
+    }
+}
+
+/// A borrowed version of [`ArchVec`].
+#[derive(Clone, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct ArchVecRef<'a> {
+    /// The architecture of the field, None is equivalent to 'any'
+    pub(crate) arch: Option<&'a str>,
+    /// The values the field contains
+    pub(crate) values: Vec<&'a str>,
+}
+
+impl<'a> ArchVecRef<'a> {
+    /// Creates a new ArchVecRef with the given architecture and values
+    pub fn with_values(arch: Option<&'a str>, values: Vec<&'a str>) -> ArchVecRef<'a> {
+        ArchVecRef { arch, values }
+    }
+
+    /// An iterator over the values in this ArchVecRef
+    pub fn iter(&self) -> impl Iterator<Item = &'a str> {
+        self.values.iter().copied()
+    }
+
+    /// A list of the ArchVecRef's values
+    pub fn values(&self) -> &[&'a str] {
+        &self.values
+    }
+
+    /// Gets the architecture
+    pub fn arch(&self) -> Option<&'a str> {
+        self.arch
+    }
+
+    /// Checks if a given ArchVecRef is supported by a given architecture
+    ///
+    /// An ArchVecRef supports an architecture if the architecture is the same as the
+    /// ArchVecRef's or the ArchVecRef does not have an architecture.
+    pub fn supports<S: AsRef<str>>(&self, s: S) -> bool {
+        self.arch.is_none_or(|a| a == s.as_ref())
+    }
+
+    /// Copies the values into an owned [`ArchVec`]
+    pub fn to_owned(&self) -> ArchVec {
+        ArchVec {
+            arch: self.arch.map(str::to_string),
+            values: self.values.iter().map(|v| v.to_string()).collect(),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -211,4 +376,56 @@ mod tests {
 
         assert_eq!(expected, depends);
     }
+
+    #[test]
+    fn test_archvecs_edit() {
+        let mut avs = ArchVecs::new();
+        avs.push(None::<&str>, "a");
+        avs.push(Some("x86_64"), "b");
+        avs.push(Some("x86_64"), "c");
+        avs.push(None::<&str>, "b");
+        assert_eq!(
+            avs.arch("x86_64").collect::<Vec<_>>(),
+            vec!["a", "b", "b", "c"]
+        );
+
+        assert!(avs.remove(None::<&str>, "b"));
+        assert!(!avs.remove(Some("i686"), "b"));
+        assert_eq!(avs.arch("x86_64").collect::<Vec<_>>(), vec!["a", "b", "c"]);
+
+        avs.retain(Some("x86_64"), |v| v == "c");
+        assert_eq!(avs.get(Some("x86_64")).unwrap().values(), ["c"]);
+
+        avs.retain(Some("x86_64"), |_| false);
+        assert!(avs.get(Some("x86_64")).is_none());
+        assert_eq!(avs.len(), 1);
+
+        avs.vecs.push(ArchVec::new(Some("i686")));
+        assert!(!avs.remove(None::<&str>, "zzz"));
+        avs.retain(None::<&str>, |_| true);
+        assert_eq!(avs.get(Some("i686")).unwrap().values(), [] as [&str; 0]);
+        assert_eq!(avs.len(), 2);
+    }
+
+    #[test]
+    fn test_archvec_ref() {
+        let av = ArchVecRef::with_values(Some("x86_64"), vec!["a", "b"]);
+        assert!(av.supports("x86_64"));
+        assert!(!av.supports("i686"));
+        assert_eq!(
+            av.to_owned(),
+            ArchVec::with_values(Some("x86_64"), vec!["a".to_string(), "b".to_string()])
+        );
+
+        let avs = ArchVecsRef::from(vec![
+            ArchVecRef::with_values(None, vec!["a"]),
+            av,
+            ArchVecRef::with_values(Some("i686"), vec!["c"]),
+        ]);
+        assert_eq!(avs.arch("x86_64").collect::<Vec<_>>(), vec!["a", "a", "b"]);
+        assert_eq!(avs.arch("i686").collect::<Vec<_>>(), vec!["a", "c"]);
+        assert_eq!(avs.any().collect::<Vec<_>>(), vec!["a"]);
+        assert_eq!(avs.all().count(), 4);
+        assert_eq!(avs.get(Some("i686")).unwrap().values(), &["c"]);
+    }
 }
@@ -0,0 +1,123 @@
+use std::path::Path;
+
+use tokio::io::{AsyncBufRead, AsyncReadExt};
+
+use crate::error::Error;
+use crate::options::{ParseOptions, decode_owned};
+use crate::srcinfo::Srcinfo;
+
+impl ParseOptions {
+    /// Parse an AsyncBufRead.
+    ///
+    /// Requires the `tokio` feature.
+    pub async fn parse_async_buf<T: AsyncBufRead + Unpin>(
+        &self,
+        mut b: T,
+    ) -> Result<Srcinfo, Error> {
+        let mut bytes = Vec::new();
+        b.read_to_end(&mut bytes).await?;
+        self.parse_str(&decode_owned(bytes, self.lossy_utf8)?)
+    }
+
+    /// Parse the file at a given path without blocking.
+    ///
+    /// Requires the `tokio` feature.
+    pub async fn parse_path_async<P: AsRef<Path>>(&self, s: P) -> Result<Srcinfo, Error> {
+        let bytes = tokio::fs::read(s).await?;
+        self.parse_str(&decode_owned(bytes, self.lossy_utf8)?)
+    }
+}
+
+impl Srcinfo {
+    /// Parse an AsyncBufRead.
+    ///
+    /// Errors are the same as [`Srcinfo::from_buf`].
+    ///
+    /// Requires the `tokio` feature.
+    ///
+    /// ```
+    /// # use srcinfo::Error;
+    /// use srcinfo::Srcinfo;
+    ///
+    /// # async fn test() -> Result<(), Error> {
+    /// let buf = "
+    /// pkgbase = example
+    /// pkgver = 1.5.0
+    /// pkgrel = 5
+    ///
+    /// pkgname = example".as_bytes();
+    ///
+    /// Srcinfo::from_async_buf(buf).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn from_async_buf<T: AsyncBufRead + Unpin>(b: T) -> Result<Srcinfo, Error> {
+        ParseOptions::default().parse_async_buf(b).await
+    }
+
+    /// Parse the file at a given path without blocking.
+    ///
+    /// Errors are the same as [`Srcinfo::from_path`].
+    ///
+    /// Requires the `tokio` feature.
+    ///
+    /// ```
+    /// # use srcinfo::Error;
+    /// use srcinfo::Srcinfo;
+    ///
+    /// # async fn test() -> Result<(), Error> {
+    /// let file = ".SRCINFO";
+    /// # let file = "tests/srcinfo/libc++";
+    /// let srcinfo = Srcinfo::from_path_async(file).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn from_path_async<P: AsRef<Path>>(s: P) -> Result<Srcinfo, Error> {
+        ParseOptions::default().parse_path_async(s).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::ErrorKind;
+    use std::fs;
+
+    #[tokio::test]
+    async fn test_from_async_buf() {
+        for file in fs::read_dir("tests/srcinfo/good").unwrap() {
+            let path = file.unwrap().path();
+            let input = fs::read(&path).unwrap();
+
+            let srcinfo = Srcinfo::from_async_buf(input.as_slice()).await.unwrap();
+            assert_eq!(srcinfo, Srcinfo::from_buf(input.as_slice()).unwrap());
+
+            let srcinfo = Srcinfo::from_path_async(&path).await.unwrap();
+            assert_eq!(srcinfo, Srcinfo::from_path(&path).unwrap());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_async_errors() {
+        for file in ["no_key", "duplicate_pkgbase", "undeclared_arch", "latin1"] {
+            let path = format!("tests/srcinfo/{}", file);
+            let input = fs::read(&path).unwrap();
+
+            let err = Srcinfo::from_async_buf(input.as_slice()).await.unwrap_err();
+            let sync_err = Srcinfo::from_buf(input.as_slice()).unwrap_err();
+            assert_eq!(err.to_string(), sync_err.to_string());
+            assert_eq!(err.line, sync_err.line);
+
+            let err = Srcinfo::from_path_async(&path).await.unwrap_err();
+            assert_eq!(err.to_string(), sync_err.to_string());
+        }
+
+        let err = Srcinfo::from_path_async("").await.unwrap_err();
+        assert_eq!(err.line, None);
+
+        match err.kind {
+            ErrorKind::IoError(_) => {}
+            _ => panic!("{:?}", err),
+        }
+    }
+}
@@ -0,0 +1,437 @@
+use crate::error::Error;
+use crate::generator::Generator;
+use crate::provenance::{Provenance, inherit_fields};
+use crate::srcinfo::{Package, Srcinfo};
+
+macro_rules! set {
+    ($fn:ident, $( $field:ident ).+) => {
+        #[doc = concat!("Sets `", stringify!($fn), "`.")]
+        pub fn $fn<S: Into<String>>(mut self, value: S) -> Self {
+            self. $( $field ).+ = value.into();
+            self
+        }
+    };
+}
+
+// a field set on a PackageBuilder overrides the pkgbase
+macro_rules! overridden {
+    ($slf:ident, $arch:expr, pkg . $field:ident) => {
+        $slf.pkg
+            .set_provenance(stringify!($field), $arch, Provenance::Overridden);
+    };
+    ($slf:ident, $arch:expr, $( $field:ident ).+) => {};
+}
+
+macro_rules! set_option {
+    ($fn:ident, $( $field:ident ).+) => {
+        #[doc = concat!("Sets `", stringify!($fn), "`.")]
+        pub fn $fn<S: Into<String>>(mut self, value: S) -> Self {
+            self. $( $field ).+ = Some(value.into());
+            overridden!(self, None::<&str>, $( $field ).+);
+            self
+        }
+    };
+}
+
+macro_rules! push {
+    ($fn:ident, $( $field:ident ).+) => {
+        #[doc = concat!("Adds a value to `", stringify!($fn), "`.")]
+        pub fn $fn<S: Into<String>>(mut self, value: S) -> Self {
+            self. $( $field ).+ .push(value.into());
+            overridden!(self, None::<&str>, $( $field ).+);
+            self
+        }
+    };
+}
+
+macro_rules! push_arch {
+    ($fn:ident, $fn_arch:ident, $( $field:ident ).+) => {
+        #[doc = concat!("Adds a value to `", stringify!($fn), "`.")]
+        pub fn $fn<S: Into<String>>(mut self, value: S) -> Self {
+            self. $( $field ).+ .push(None::<String>, value);
+            overridden!(self, None::<&str>, $( $field ).+);
+            self
+        }
+
+        #[doc = concat!("Adds a value to `", stringify!($fn), "_$arch`.")]
+        pub fn $fn_arch<A: Into<String>, S: Into<String>>(mut self, arch: A, value: S) -> Self {
+            let arch = arch.into();
+            overridden!(self, Some(arch.as_str()), $( $field ).+);
+            self. $( $field ).+ .push(Some(arch), value);
+            self
+        }
+    };
+}
+
+macro_rules! clear {
+    ($fn:ident, $field:ident) => {
+        #[doc = concat!(
+            "Clears `", stringify!($field), "` so that it is not inherited from the pkgbase."
+        )]
+        pub fn $fn(mut self) -> Self {
+            self.pkg.$fn();
+            self
+        }
+    };
+}
+
+macro_rules! clear_arch {
+    ($fn:ident, $fn_arch:ident, $field:ident) => {
+        #[doc = concat!(
+            "Clears `", stringify!($field), "` so that it is not inherited from the pkgbase."
+        )]
+        ///
+        /// Like an empty value in a pkgname section this does not clear the architecture
+        /// specific values.
+        pub fn $fn(mut self) -> Self {
+            self.pkg.$fn(None::<&str>);
+            self
+        }
+
+        #[doc = concat!(
+            "Clears `", stringify!($field), "_$arch` so that it is not inherited from the pkgbase."
+        )]
+        pub fn $fn_arch<A: Into<String>>(mut self, arch: A) -> Self {
+            self.pkg.$fn(Some(arch.into()));
+            self
+        }
+    };
+}
+
+/// A builder for a [`Srcinfo`].
+///
+/// Fields that belong to packages set on the SrcinfoBuilder are the defaults that every
+/// [`PackageBuilder`] inherits unless it sets or clears the field itself.
+///
+/// ```
+/// use srcinfo::{PackageBuilder, SrcinfoBuilder};
+///
+/// let srcinfo = SrcinfoBuilder::new("example", "1.5.0", "5")
+///     .pkgdesc("An example")
+///     .arch("x86_64")
+///     .arch("aarch64")
+///     .source("example.tar.gz")
+///     .sha256sums("SKIP")
+///     .depends("glibc")
+///     .depends_arch("x86_64", "lib32-glibc")
+///     .package(PackageBuilder::new("example"))
+///     .package(
+///         PackageBuilder::new("example-docs")
+///             .pkgdesc("Documentation for example")
+///             .arch("any")
+///             .clear_depends()
+///             .clear_depends_arch("x86_64"),
+///     )
+///     .build()
+///     .unwrap();
+///
+/// assert_eq!(srcinfo.pkg("example").unwrap().depends().arch("x86_64").count(), 2);
+/// assert!(srcinfo.pkg("example-docs").unwrap().depends().is_empty());
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct SrcinfoBuilder {
+    srcinfo: Srcinfo,
+    pkgs: Vec<PackageBuilder>,
+}
+
+impl SrcinfoBuilder {
+    /// Creates a new SrcinfoBuilder with the required pkgbase fields.
+    pub fn new<B, V, R>(pkgbase: B, pkgver: V, pkgrel: R) -> SrcinfoBuilder
+    where
+        B: Into<String>,
+        V: Into<String>,
+        R: Into<String>,
+    {
+        SrcinfoBuilder::default()
+            .pkgbase(pkgbase)
+            .pkgver(pkgver)
+            .pkgrel(pkgrel)
+    }
+
+    /// Adds a package.
+    pub fn package(mut self, pkg: PackageBuilder) -> Self {
+        self.pkgs.push(pkg);
+        self
+    }
+
+    /// Sets the tool that generated the .SRCINFO.
+    pub fn generator(mut self, generator: Generator) -> Self {
+        self.srcinfo.set_generator(generator);
+        self
+    }
+
+    /// Builds the Srcinfo.
+    ///
+    /// The result is checked the same way as the parser checks a .SRCINFO and every problem
+    /// found is returned: the required fields must be set and the architecture of every
+    /// architecture specific field must be declared. A pkgname can also not be used twice,
+    /// which the parser only rejects with [`ParseOptions::strict`](crate::ParseOptions::strict).
+    ///
+    /// Use [`Srcinfo::validate`] on the result to also check the checksums and package
+    /// architectures.
+    pub fn build(self) -> Result<Srcinfo, Vec<Error>> {
+        let mut srcinfo = self.srcinfo;
+        for pkg in self.pkgs {
+            let pkg = pkg.merge(&srcinfo.pkg);
+            srcinfo.pkgs.push(pkg);
+        }
+
+        srcinfo.check_built()?;
+        Ok(srcinfo)
+    }
+
+    set!(comment, srcinfo.comment);
+    set!(pkgbase, srcinfo.base.pkgbase);
+    set!(pkgver, srcinfo.base.pkgver);
+    set!(pkgrel, srcinfo.base.pkgrel);
+    set_option!(epoch, srcinfo.base.epoch);
+    push_arch!(source, source_arch, srcinfo.base.source);
+    push!(valid_pgp_keys, srcinfo.base.valid_pgp_keys);
+    push!(no_extract, srcinfo.base.no_extract);
+    push_arch!(cksums, cksums_arch, srcinfo.base.cksums);
+    push_arch!(md5sums, md5sums_arch, srcinfo.base.md5sums);
+    push_arch!(sha1sums, sha1sums_arch, srcinfo.base.sha1sums);
+    push_arch!(sha224sums, sha224sums_arch, srcinfo.base.sha224sums);
+    push_arch!(sha256sums, sha256sums_arch, srcinfo.base.sha256sums);
+    push_arch!(sha384sums, sha384sums_arch, srcinfo.base.sha384sums);
+    push_arch!(sha512sums, sha512sums_arch, srcinfo.base.sha512sums);
+    push_arch!(b2sums, b2sums_arch, srcinfo.base.b2sums);
+    push_arch!(makedepends, makedepends_arch, srcinfo.base.makedepends);
+    push_arch!(checkdepends, checkdepends_arch, srcinfo.base.checkdepends);
+
+    set_option!(pkgdesc, srcinfo.pkg.pkgdesc);
+    push!(arch, srcinfo.pkg.arch);
+    set_option!(url, srcinfo.pkg.url);
+    push!(license, srcinfo.pkg.license);
+    push!(groups, srcinfo.pkg.groups);
+    push_arch!(depends, depends_arch, srcinfo.pkg.depends);
+    push_arch!(optdepends, optdepends_arch, srcinfo.pkg.optdepends);
+    push_arch!(provides, provides_arch, srcinfo.pkg.provides);
+    push_arch!(conflicts, conflicts_arch, srcinfo.pkg.conflicts);
+    push_arch!(replaces, replaces_arch, srcinfo.pkg.replaces);
+    push!(backup, srcinfo.pkg.backup);
+    push!(options, srcinfo.pkg.options);
+    set_option!(install, srcinfo.pkg.install);
+    set_option!(changelog, srcinfo.pkg.changelog);
+}
+
+/// A builder for a [`Package`], added to a [`SrcinfoBuilder`] with
+/// [`SrcinfoBuilder::package`].
+///
+/// Fields that are not set are inherited from the [`SrcinfoBuilder`]. The `clear_` methods
+/// stop a field from being inherited, the same as an empty value in a pkgname section.
+#[derive(Clone, Debug, Default)]
+pub struct PackageBuilder {
+    pkg: Package,
+}
+
+impl PackageBuilder {
+    /// Creates a new PackageBuilder.
+    pub fn new<S: Into<String>>(pkgname: S) -> PackageBuilder {
+        PackageBuilder::default().pkgname(pkgname)
+    }
+
+    pub(crate) fn merge(mut self, base: &Package) -> Package {
+        inherit_fields!(&mut self.pkg, base);
+        self.pkg
+    }
+
+    set!(pkgname, pkg.pkgname);
+    set_option!(pkgdesc, pkg.pkgdesc);
+    push!(arch, pkg.arch);
+    set_option!(url, pkg.url);
+    push!(license, pkg.license);
+    push!(groups, pkg.groups);
+    push_arch!(depends, depends_arch, pkg.depends);
+    push_arch!(optdepends, optdepends_arch, pkg.optdepends);
+    push_arch!(provides, provides_arch, pkg.provides);
+    push_arch!(conflicts, conflicts_arch, pkg.conflicts);
+    push_arch!(replaces, replaces_arch, pkg.replaces);
+    push!(backup, pkg.backup);
+    push!(options, pkg.options);
+    set_option!(install, pkg.install);
+    set_option!(changelog, pkg.changelog);
+
+    clear!(clear_pkgdesc, pkgdesc);
+    clear!(clear_architectures, arch);
+    clear!(clear_url, url);
+    clear!(clear_license, license);
+    clear!(clear_groups, groups);
+    clear_arch!(clear_depends, clear_depends_arch, depends);
+    clear_arch!(clear_optdepends, clear_optdepends_arch, optdepends);
+    clear_arch!(clear_provides, clear_provides_arch, provides);
+    clear_arch!(clear_conflicts, clear_conflicts_arch, conflicts);
+    clear_arch!(clear_replaces, clear_replaces_arch, replaces);
+    clear!(clear_backup, backup);
+    clear!(clear_options, options);
+    clear!(clear_install, install);
+    clear!(clear_changelog, changelog);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::ErrorKind;
+
+    #[test]
+    fn test_builder() {
+        let srcinfo = include_str!("../tests/srcinfo/libc++")
+            .parse::<Srcinfo>()
+            .unwrap();
+
+        let mut builder = SrcinfoBuilder::new("libc++", "6.0.0", "1")
+            .url("https://libcxx.llvm.org/")
+            .arch("i686")
+            .arch("x86_64")
+            .license("MIT")
+            .license("custom:University of Illinois/NCSA Open Source License")
+            .depends("gcc-libs");
+        for source in srcinfo.source().all() {
+            builder = builder.source(source);
+        }
+        for sum in srcinfo.sha512sums().all() {
+            builder = builder.sha512sums(sum);
+        }
+        for key in srcinfo.valid_pgp_keys() {
+            builder = builder.valid_pgp_keys(key);
+        }
+        for depend in srcinfo.makedepends().all() {
+            builder = builder.makedepends(depend);
+        }
+        for name in srcinfo.no_extract() {
+            builder = builder.no_extract(name);
+        }
+
+        let built = builder
+            .package(
+                PackageBuilder::new("libc++")
+                    .pkgdesc("LLVM C++ standard library.")
+                    .depends("libc++abi=6.0.0-1"),
+            )
+            .package(
+                PackageBuilder::new("libc++abi")
+                    .pkgdesc("Low level support for the LLVM C++ standard library."),
+            )
+            .package(
+                PackageBuilder::new("libc++experimental")
+                    .pkgdesc("LLVM C++ experimental library.")
+                    .depends("libc++=6.0.0-1"),
+            )
+            .build()
+            .unwrap();
+
+        assert_eq!(built, srcinfo);
+    }
+
+    #[test]
+    fn test_builder_clear() {
+        let srcinfo = SrcinfoBuilder::new("foo", "1", "1")
+            .pkgdesc("foo")
+            .arch("x86_64")
+            .arch("i686")
+            .provides("foo")
+            .provides_arch("x86_64", "foo-x86_64")
+            .provides_arch("i686", "foo-i686")
+            .package(PackageBuilder::new("foo"))
+            .package(
+                PackageBuilder::new("bar")
+                    .clear_pkgdesc()
+                    .clear_architectures()
+                    .arch("x86_64")
+                    .clear_provides_arch("x86_64"),
+            )
+            .package(
+                PackageBuilder::new("baz")
+                    .provides_arch("i686", "baz-i686")
+                    .clear_provides(),
+            )
+            .build()
+            .unwrap();
+
+        let (foo, bar, baz) = (&srcinfo.pkgs[0], &srcinfo.pkgs[1], &srcinfo.pkgs[2]);
+        assert_eq!(foo.pkgdesc(), Some("foo"));
+        assert_eq!(foo.provides().len(), 3);
+        assert_eq!(bar.pkgdesc(), None);
+        assert_eq!(bar.arch(), &["x86_64"]);
+        assert_eq!(
+            bar.provides().all().collect::<Vec<_>>(),
+            vec!["foo", "foo-i686"]
+        );
+        assert_eq!(
+            baz.provides().all().collect::<Vec<_>>(),
+            vec!["baz-i686", "foo-x86_64"]
+        );
+        assert_eq!(baz.provenance("provides", None), Provenance::Cleared);
+
+        let output = srcinfo.to_string();
+        assert!(output.contains("pkgname = bar\n\tpkgdesc = \n\tarch = x86_64\n"));
+        assert_eq!(output.parse::<Srcinfo>().unwrap(), srcinfo);
+    }
+
+    #[test]
+    fn test_builder_validate() {
+        let errors = SrcinfoBuilder::default()
+            .pkgbase("foo")
+            .arch("x86_64")
+            .depends_arch("i686", "bar")
+            .package(PackageBuilder::new("foo"))
+            .package(PackageBuilder::new("foo"))
+            .build()
+            .unwrap_err();
+
+        let kinds = errors.iter().map(|e| &e.kind).collect::<Vec<_>>();
+        assert!(matches!(kinds[0], ErrorKind::MissingField(f) if f == "pkgver"));
+        assert!(matches!(kinds[1], ErrorKind::MissingField(f) if f == "pkgrel"));
+        assert!(matches!(kinds[2], ErrorKind::DuplicatePkgname(p) if p == "foo"));
+        assert!(
+            matches!(kinds[3], ErrorKind::UndeclaredArch(k, a) if k == "depends_i686" && a == "i686")
+        );
+        assert_eq!(kinds.len(), 4);
+    }
+
+    #[test]
+    fn test_builder_parser_rules() {
+        let input = "
+pkgbase = a
+pkgver = 1
+pkgrel = 1
+arch = x86_64
+source = a.tar.gz
+
+pkgname = a
+arch = i686";
+
+        let built = SrcinfoBuilder::new("a", "1", "1")
+            .arch("x86_64")
+            .source("a.tar.gz")
+            .package(PackageBuilder::new("a").arch("i686"))
+            .build()
+            .unwrap();
+
+        assert_eq!(built, input.parse().unwrap());
+
+        let kinds = built
+            .validate()
+            .unwrap_err()
+            .into_iter()
+            .map(|e| e.kind)
+            .collect::<Vec<_>>();
+        assert!(
+            matches!(&kinds[0], ErrorKind::UndeclaredPackageArch(p, a) if p == "a" && a == "i686")
+        );
+        assert!(matches!(kinds[1], ErrorKind::MissingChecksums(None)));
+    }
+
+    #[test]
+    fn test_builder_duplicate_pkgname() {
+        let errors = SrcinfoBuilder::new("foo", "1", "1")
+            .package(PackageBuilder::new("bar").pkgdesc("b"))
+            .package(PackageBuilder::new("foo"))
+            .package(PackageBuilder::new("bar"))
+            .build()
+            .unwrap_err();
+
+        assert_eq!(errors.len(), 1);
+        assert!(matches!(&errors[0].kind, ErrorKind::DuplicatePkgname(p) if p == "bar"));
+    }
+}
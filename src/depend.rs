@@ -0,0 +1,437 @@
+use std::cmp::Ordering;
+use std::str::FromStr;
+
+use crate::archvec::ArchVecs;
+use crate::error::{Error, ErrorKind};
+use crate::srcinfo::Package;
+use crate::version::Version;
+use crate::view::PackageView;
+
+/// A version comparison operator used by a [`Dependency`].
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Op {
+    /// `<`
+    Lt,
+    /// `<=`
+    Le,
+    /// `=`
+    Eq,
+    /// `>=`
+    Ge,
+    /// `>`
+    Gt,
+}
+
+impl Op {
+    /// Returns the operator as it is written in a dependency, such as ">=".
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Op::Lt => "<",
+            Op::Le => "<=",
+            Op::Eq => "=",
+            Op::Ge => ">=",
+            Op::Gt => ">",
+        }
+    }
+
+    /// Checks if the result of comparing a version to the required version matches this
+    /// operator.
+    pub fn matches(&self, ord: Ordering) -> bool {
+        match self {
+            Op::Lt => ord == Ordering::Less,
+            Op::Le => ord != Ordering::Greater,
+            Op::Eq => ord == Ordering::Equal,
+            Op::Ge => ord != Ordering::Less,
+            Op::Gt => ord == Ordering::Greater,
+        }
+    }
+}
+
+/// A dependency in the format "name", or "name" followed by an [`Op`] and a [`Version`].
+///
+/// This is the format used by `depends`, `makedepends`, `checkdepends`, `provides`,
+/// `conflicts` and `replaces`.
+///
+/// ```
+/// # use srcinfo::Error;
+/// use srcinfo::{Dependency, Op};
+///
+/// # fn test() -> Result<(), Error> {
+/// let dep: Dependency = "python>=3.11".parse()?;
+/// assert_eq!(dep.name, "python");
+/// assert_eq!(dep.constraint, Some((Op::Ge, "3.11".parse()?)));
+/// assert_eq!(dep.to_string(), "python>=3.11");
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Dependency {
+    /// The name of the dependency
+    pub name: String,
+    /// The version the dependency is restricted to, if any
+    pub constraint: Option<(Op, Version)>,
+}
+
+impl Dependency {
+    /// Creates a new Dependency.
+    pub fn new<S: Into<String>>(name: S, constraint: Option<(Op, Version)>) -> Dependency {
+        Dependency {
+            name: name.into(),
+            constraint,
+        }
+    }
+
+    /// Checks if a version matches the constraint of this dependency.
+    ///
+    /// Always true when there is no constraint.
+    pub fn matches_version(&self, version: &Version) -> bool {
+        match &self.constraint {
+            Some((op, required)) => op.matches(version.vercmp(required)),
+            None => true,
+        }
+    }
+}
+
+impl FromStr for Dependency {
+    type Err = Error;
+
+    /// Parses a dependency such as "foo", "foo=1.0-1" or "foo>=1:1.0".
+    ///
+    /// Like pacman the name ends at the first '<', '>' or '='. Returns
+    /// [`ErrorKind::InvalidDependency`] if the name or the version is empty.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || Error::from(ErrorKind::InvalidDependency(s.to_string()));
+
+        let (name, constraint) = match s.find(['<', '>', '=']) {
+            Some(i) => {
+                let (name, rest) = s.split_at(i);
+                let (op, version) = match rest.as_bytes() {
+                    [b'<', b'=', ..] => (Op::Le, &rest[2..]),
+                    [b'>', b'=', ..] => (Op::Ge, &rest[2..]),
+                    [b'<', ..] => (Op::Lt, &rest[1..]),
+                    [b'>', ..] => (Op::Gt, &rest[1..]),
+                    _ => (Op::Eq, &rest[1..]),
+                };
+                let version = version.parse().map_err(|_| invalid())?;
+                (name, Some((op, version)))
+            }
+            None => (s, None),
+        };
+
+        if name.is_empty() {
+            return Err(invalid());
+        }
+
+        Ok(Dependency::new(name, constraint))
+    }
+}
+
+/// Checks if a dependency is satisfied by a provider.
+///
+/// The provider is either a package, given as its name and an [`Op::Eq`] constraint on its
+/// version, or an entry of a package's `provides`. This follows pacman's rules: the names
+/// must match, and a dependency with a constraint is only satisfied by a provider with an
+/// `=` version that matches the constraint. A provide without a version never satisfies a
+/// versioned dependency.
+///
+/// ```
+/// # use srcinfo::Error;
+/// use srcinfo::{satisfies, Dependency};
+///
+/// # fn test() -> Result<(), Error> {
+/// let dep: Dependency = "sh>=5".parse()?;
+/// assert!(satisfies(&dep, &"sh=5.2.015-1".parse()?));
+/// assert!(!satisfies(&dep, &"sh=4.4".parse()?));
+/// assert!(!satisfies(&dep, &"sh".parse()?));
+/// assert!(satisfies(&"sh".parse()?, &"sh".parse()?));
+/// # Ok(())
+/// # }
+/// ```
+pub fn satisfies(dep: &Dependency, provider: &Dependency) -> bool {
+    if dep.name != provider.name {
+        return false;
+    }
+
+    match (&dep.constraint, &provider.constraint) {
+        (None, _) => true,
+        (Some(_), Some((Op::Eq, version))) => dep.matches_version(version),
+        (Some(_), _) => false,
+    }
+}
+
+/// An optional dependency in the format "dependency: description".
+///
+/// ```
+/// # use srcinfo::Error;
+/// use srcinfo::OptDepend;
+///
+/// # fn test() -> Result<(), Error> {
+/// let optdep: OptDepend = "python-foo>=2: for the foo backend".parse()?;
+/// assert_eq!(optdep.dep.name, "python-foo");
+/// assert_eq!(optdep.description.as_deref(), Some("for the foo backend"));
+/// assert_eq!(optdep.to_string(), "python-foo>=2: for the foo backend");
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct OptDepend {
+    /// The dependency
+    pub dep: Dependency,
+    /// The description of what the dependency is needed for
+    pub description: Option<String>,
+}
+
+impl OptDepend {
+    /// Creates a new OptDepend.
+    pub fn new<S: Into<String>>(dep: Dependency, description: Option<S>) -> OptDepend {
+        OptDepend {
+            dep,
+            description: description.map(Into::into),
+        }
+    }
+}
+
+impl FromStr for OptDepend {
+    type Err = Error;
+
+    /// Parses an optional dependency such as "foo" or "foo>=1: for bar".
+    ///
+    /// Like pacman the description starts after the first ": ". A ':' that is not followed by
+    /// a space, such as the one in "foo=1:2.0", does not start a description. Any other
+    /// whitespace is kept as is. Returns [`ErrorKind::InvalidDependency`] if the dependency
+    /// is invalid.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.split_once(": ") {
+            Some((dep, description)) => Ok(OptDepend::new(dep.parse()?, Some(description))),
+            None => Ok(OptDepend::new(s.parse()?, None::<String>)),
+        }
+    }
+}
+
+impl Package {
+    /// Parses the `optdepends` that apply to the given architecture.
+    ///
+    /// See [`OptDepend`].
+    pub fn optdepends_typed<S: AsRef<str>>(
+        &self,
+        arch: S,
+    ) -> impl Iterator<Item = Result<OptDepend, Error>> {
+        self.optdepends.arch(arch).map(str::parse)
+    }
+}
+
+impl PackageView<'_> {
+    /// Checks if this package satisfies a dependency on the given architecture.
+    ///
+    /// The dependency is satisfied by the package itself or by one of its `provides`, see
+    /// [`satisfies`]. Provides that fail to parse are ignored.
+    pub fn satisfies<S: AsRef<str>>(&self, arch: S, dep: &Dependency) -> bool {
+        let version = Version::new(self.epoch(), self.pkgver(), Some(self.pkgrel()));
+        let pkg = Dependency::new(self.pkgname(), Some((Op::Eq, version)));
+
+        satisfies(dep, &pkg)
+            || self
+                .provides()
+                .arch_deps(arch)
+                .filter_map(Result::ok)
+                .any(|provide| satisfies(dep, &provide))
+    }
+}
+
+impl ArchVecs {
+    /// Parses the values that apply to the given architecture as dependencies
+    ///
+    /// See [`ArchVecs::arch`].
+    pub fn arch_deps<S: AsRef<str>>(
+        &self,
+        arch: S,
+    ) -> impl Iterator<Item = Result<Dependency, Error>> {
+        self.arch(arch).map(str::parse)
+    }
+
+    /// Parses all values in this ArchVecs as dependencies
+    ///
+    /// See [`ArchVecs::all`].
+    pub fn all_deps(&self) -> impl Iterator<Item = Result<Dependency, Error>> {
+        self.all().map(str::parse)
+    }
+
+    /// Parses the values that have no specific architecture as dependencies
+    ///
+    /// See [`ArchVecs::any`].
+    pub fn any_deps(&self) -> impl Iterator<Item = Result<Dependency, Error>> {
+        self.any().map(str::parse)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Srcinfo;
+
+    fn dep(s: &str) -> Dependency {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_parse_dependency() {
+        let version = |s: &str| s.parse::<Version>().unwrap();
+        let cases = [
+            ("foo", "foo", None),
+            ("foo<1", "foo", Some((Op::Lt, version("1")))),
+            ("foo<=1", "foo", Some((Op::Le, version("1")))),
+            ("foo=1:1.0-2", "foo", Some((Op::Eq, version("1:1.0-2")))),
+            ("foo>=1", "foo", Some((Op::Ge, version("1")))),
+            ("foo>1", "foo", Some((Op::Gt, version("1")))),
+            (
+                "libc++abi=6.0.0-1",
+                "libc++abi",
+                Some((Op::Eq, version("6.0.0-1"))),
+            ),
+            (
+                "libfoo.so=1-64",
+                "libfoo.so",
+                Some((Op::Eq, version("1-64"))),
+            ),
+        ];
+
+        for (s, name, constraint) in cases {
+            let dep = dep(s);
+            assert_eq!(dep.name, name);
+            assert_eq!(dep.constraint, constraint);
+            assert_eq!(dep.to_string(), s);
+        }
+
+        for s in ["", ">=1", "foo>=", "foo=", "foo<=-1"] {
+            let err = s.parse::<Dependency>().unwrap_err();
+            assert!(matches!(err.kind, ErrorKind::InvalidDependency(d) if d == s));
+        }
+    }
+
+    #[test]
+    fn test_satisfies() {
+        let cases = [
+            ("foo", "foo", true),
+            ("foo", "foo=1", true),
+            ("foo", "bar", false),
+            ("foo", "bar=1", false),
+            ("foo=1", "foo", false),
+            ("foo>=1", "foo", false),
+            ("foo=1", "foo=1", true),
+            ("foo=1", "foo=1-3", true),
+            ("foo=1-2", "foo=1-3", false),
+            ("foo=1.0", "foo=1.00", true),
+            ("foo<2", "foo=1", true),
+            ("foo<1", "foo=1", false),
+            ("foo<=1", "foo=1", true),
+            ("foo>=1", "foo=1", true),
+            ("foo>=1", "foo=1:0.5", true),
+            ("foo>1", "foo=1", false),
+            ("foo>1", "foo=1.1", true),
+            ("foo>=1", "foo>=2", false),
+            ("foo<=1", "foo<1", false),
+        ];
+
+        for (d, p, expected) in cases {
+            assert_eq!(satisfies(&dep(d), &dep(p)), expected, "{} {}", d, p);
+        }
+    }
+
+    #[test]
+    fn test_archvecs_deps() {
+        let srcinfo = Srcinfo::from_path("tests/srcinfo/libc++").unwrap();
+        let pkg = srcinfo.pkg("libc++").unwrap();
+
+        let deps = pkg
+            .depends
+            .arch_deps("x86_64")
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(deps, vec![dep("libc++abi=6.0.0-1")]);
+        assert_eq!(pkg.depends.all_deps().count(), pkg.depends.all().count());
+        assert_eq!(pkg.depends.any_deps().count(), pkg.depends.any().count());
+    }
+
+    #[test]
+    fn test_parse_optdepend() {
+        let cases = [
+            ("foo", "foo", None),
+            ("foo: bar", "foo", Some("bar")),
+            ("foo: bar: baz", "foo", Some("bar: baz")),
+            ("foo:  bar ", "foo", Some(" bar ")),
+            ("foo: ", "foo", Some("")),
+            ("foo=1:2.0-1: bar", "foo=1:2.0-1", Some("bar")),
+            ("foo=1:2.0", "foo=1:2.0", None),
+        ];
+
+        for (s, d, description) in cases {
+            let optdep = s.parse::<OptDepend>().unwrap();
+            assert_eq!(optdep.dep, dep(d));
+            assert_eq!(optdep.description.as_deref(), description);
+            assert_eq!(optdep.to_string(), s);
+        }
+
+        for s in ["", ": bar", "foo=: bar"] {
+            let err = s.parse::<OptDepend>().unwrap_err();
+            assert!(matches!(err.kind, ErrorKind::InvalidDependency(_)));
+        }
+    }
+
+    #[test]
+    fn test_optdepends_typed() {
+        let srcinfo: Srcinfo = "
+pkgbase = a
+pkgver = 1
+pkgrel = 1
+arch = x86_64
+arch = i686
+optdepends = python-foo: for the foo backend
+optdepends_x86_64 = bar>=2
+
+pkgname = a"
+            .parse()
+            .unwrap();
+        let pkg = srcinfo.pkg("a").unwrap();
+
+        let optdeps = pkg
+            .optdepends_typed("x86_64")
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(
+            optdeps,
+            vec![
+                OptDepend::new(dep("python-foo"), Some("for the foo backend")),
+                OptDepend::new(dep("bar>=2"), None::<String>),
+            ]
+        );
+        assert_eq!(pkg.optdepends_typed("i686").count(), 1);
+    }
+
+    #[test]
+    fn test_package_satisfies() {
+        let srcinfo: Srcinfo = "
+pkgbase = a
+pkgver = 2.0
+pkgrel = 1
+arch = x86_64
+arch = i686
+
+pkgname = a
+provides = sh
+provides_x86_64 = liba.so=1-64"
+            .parse()
+            .unwrap();
+        let pkg = srcinfo.pkgs_resolved().next().unwrap();
+
+        assert!(pkg.satisfies("x86_64", &dep("a")));
+        assert!(pkg.satisfies("x86_64", &dep("a>=2.0-1")));
+        assert!(!pkg.satisfies("x86_64", &dep("a<2.0")));
+        assert!(pkg.satisfies("x86_64", &dep("sh")));
+        assert!(!pkg.satisfies("x86_64", &dep("sh>=5")));
+        assert!(pkg.satisfies("x86_64", &dep("liba.so=1-64")));
+        assert!(!pkg.satisfies("i686", &dep("liba.so")));
+    }
+}
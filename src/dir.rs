@@ -0,0 +1,134 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use rayon::prelude::*;
+
+use crate::error::Error;
+use crate::options::ParseOptions;
+use crate::srcinfo::Srcinfo;
+
+// recursively collects every .SRCINFO below dir, skipping .git directories and not following
+// symlinks to directories
+fn find_srcinfos(dir: &Path, found: &mut Vec<(PathBuf, Result<(), Error>)>) {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) => return found.push((dir.to_path_buf(), Err(e.into()))),
+    };
+
+    for entry in entries {
+        let entry = match entry {
+            Ok(entry) => entry,
+            Err(e) => {
+                found.push((dir.to_path_buf(), Err(e.into())));
+                continue;
+            }
+        };
+
+        let path = entry.path();
+        match entry.file_type() {
+            Ok(t) if t.is_dir() => {
+                if entry.file_name() != ".git" {
+                    find_srcinfos(&path, found);
+                }
+            }
+            Ok(_) if entry.file_name() == ".SRCINFO" => found.push((path, Ok(()))),
+            Ok(_) => (),
+            Err(e) => found.push((path, Err(e.into()))),
+        }
+    }
+}
+
+impl ParseOptions {
+    /// Find every .SRCINFO below a directory and parse them in parallel.
+    ///
+    /// Requires the `rayon` feature.
+    pub fn parse_dir<P: AsRef<Path>>(&self, root: P) -> Vec<(PathBuf, Result<Srcinfo, Error>)> {
+        let mut found = Vec::new();
+        find_srcinfos(root.as_ref(), &mut found);
+        found.sort_by(|a, b| a.0.cmp(&b.0));
+
+        found
+            .into_par_iter()
+            .map(|(path, res)| {
+                let res = res.and_then(|_| self.parse_path(&path));
+                (path, res)
+            })
+            .collect()
+    }
+}
+
+impl Srcinfo {
+    /// Find every .SRCINFO below a directory and parse them in parallel.
+    ///
+    /// Directories are searched recursively. `.git` directories and symlinks to directories
+    /// are skipped.
+    ///
+    /// Every file is returned along with the result of parsing it, a file failing to parse
+    /// does not stop the others from being parsed. Directories that can not be read are
+    /// returned with an [`ErrorKind::IoError`](crate::ErrorKind::IoError). The results are
+    /// sorted by path.
+    ///
+    /// Requires the `rayon` feature.
+    ///
+    /// ```
+    /// use srcinfo::Srcinfo;
+    ///
+    /// let dir = "aur";
+    /// # let dir = "tests/srcinfo";
+    /// for (path, srcinfo) in Srcinfo::from_dir(dir) {
+    ///     match srcinfo {
+    ///         Ok(srcinfo) => println!("{}: {}", path.display(), srcinfo.version()),
+    ///         Err(err) => eprintln!("{}: {}", path.display(), err),
+    ///     }
+    /// }
+    /// ```
+    pub fn from_dir<P: AsRef<Path>>(root: P) -> Vec<(PathBuf, Result<Srcinfo, Error>)> {
+        ParseOptions::default().parse_dir(root)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::ErrorKind;
+    use crate::test_util::{LIBCPP, TempPath};
+
+    #[test]
+    fn test_from_dir() {
+        let root = TempPath::new("from-dir");
+        root.write("a/.SRCINFO", LIBCPP);
+        root.write("a/PKGBUILD", "");
+        root.write("b/c/.SRCINFO", "pkgbase = foo");
+        root.write("b/.git/.SRCINFO", LIBCPP);
+        root.write("d/.SRCINFO", LIBCPP);
+
+        let results = Srcinfo::from_dir(&root);
+
+        let paths = results.iter().map(|(p, _)| p.clone()).collect::<Vec<_>>();
+        assert_eq!(
+            paths,
+            vec![
+                root.join("a/.SRCINFO"),
+                root.join("b/c/.SRCINFO"),
+                root.join("d/.SRCINFO"),
+            ]
+        );
+
+        assert_eq!(results[0].1.as_ref().unwrap().pkgbase(), "libc++");
+        assert_eq!(results[2].1.as_ref().unwrap().pkgbase(), "libc++");
+        match results[1].1.as_ref().unwrap_err().kind {
+            ErrorKind::MissingField(ref field) => assert_eq!(field, "pkgname"),
+            ref kind => panic!("{:?}", kind),
+        }
+    }
+
+    #[test]
+    fn test_from_dir_missing() {
+        let results = Srcinfo::from_dir("tests/does-not-exist");
+        assert_eq!(results.len(), 1);
+        assert!(matches!(
+            results[0].1.as_ref().unwrap_err().kind,
+            ErrorKind::IoError(_)
+        ));
+    }
+}
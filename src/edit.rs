@@ -0,0 +1,414 @@
+use crate::builder::PackageBuilder;
+use crate::error::{Error, ErrorKind};
+use crate::provenance::{Provenance, inherit_fields};
+use crate::srcinfo::{Package, Srcinfo};
+
+macro_rules! edit_option {
+    ($set:ident, $clear:ident, $field:ident) => {
+        #[doc = concat!(
+            "Sets `", stringify!($field), "` so that it is not inherited from the pkgbase."
+        )]
+        pub fn $set<S: Into<String>>(&mut self, value: S) {
+            self.$field = Some(value.into());
+            self.set_provenance(stringify!($field), None::<&str>, Provenance::Overridden);
+        }
+
+        #[doc = concat!(
+            "Clears `", stringify!($field), "` so that it is not inherited from the pkgbase."
+        )]
+        pub fn $clear(&mut self) {
+            self.$field = None;
+            self.set_provenance(stringify!($field), None::<&str>, Provenance::Cleared);
+        }
+    };
+}
+
+macro_rules! edit_vec {
+    ($push:ident, $remove:ident, $clear:ident, $field:ident) => {
+        #[doc = concat!(
+            "Adds a value to `", stringify!($field),
+            "` so that it is not inherited from the pkgbase."
+        )]
+        pub fn $push<S: Into<String>>(&mut self, value: S) {
+            self.$field.push(value.into());
+            self.set_provenance(stringify!($field), None::<&str>, Provenance::Overridden);
+        }
+
+        #[doc = concat!(
+            "Removes a value from `", stringify!($field),
+            "`, returning true if anything was removed."
+        )]
+        ///
+        /// The field is no longer inherited from the pkgbase. It is cleared when no values are
+        /// left.
+        pub fn $remove<S: AsRef<str>>(&mut self, value: S) -> bool {
+            let len = self.$field.len();
+            self.$field.retain(|v| v != value.as_ref());
+            if len == self.$field.len() {
+                return false;
+            }
+
+            let provenance = if self.$field.is_empty() {
+                Provenance::Cleared
+            } else {
+                Provenance::Overridden
+            };
+            self.set_provenance(stringify!($field), None::<&str>, provenance);
+            true
+        }
+
+        #[doc = concat!(
+            "Clears `", stringify!($field), "` so that it is not inherited from the pkgbase."
+        )]
+        pub fn $clear(&mut self) {
+            self.$field.clear();
+            self.set_provenance(stringify!($field), None::<&str>, Provenance::Cleared);
+        }
+    };
+}
+
+macro_rules! edit_arch_vecs {
+    ($push:ident, $remove:ident, $clear:ident, $field:ident) => {
+        #[doc = concat!(
+                    "Adds a value to `", stringify!($field), "` or `", stringify!($field),
+                    "_$arch` so that it is not inherited from the pkgbase."
+                )]
+        pub fn $push<A: Into<String>, S: Into<String>>(&mut self, arch: Option<A>, value: S) {
+            let arch = arch.map(Into::into);
+            self.set_provenance(stringify!($field), arch.as_deref(), Provenance::Overridden);
+            self.$field.push(arch, value);
+        }
+
+        #[doc = concat!(
+                    "Removes a value from `", stringify!($field), "` or `", stringify!($field),
+                    "_$arch`, returning true if anything was removed."
+                )]
+        ///
+        /// The architecture is no longer inherited from the pkgbase. It is cleared when no values
+        /// are left.
+        pub fn $remove<A: AsRef<str>, S: AsRef<str>>(&mut self, arch: Option<A>, value: S) -> bool {
+            let arch = arch.as_ref().map(|a| a.as_ref());
+            if !self.$field.remove(arch, value) {
+                return false;
+            }
+
+            let provenance = match self.$field.get(arch) {
+                Some(_) => Provenance::Overridden,
+                None => Provenance::Cleared,
+            };
+            self.set_provenance(stringify!($field), arch, provenance);
+            true
+        }
+
+        #[doc = concat!(
+                    "Clears `", stringify!($field), "` or `", stringify!($field),
+                    "_$arch` so that it is not inherited from the pkgbase."
+                )]
+        pub fn $clear<A: AsRef<str>>(&mut self, arch: Option<A>) {
+            let arch = arch.as_ref().map(|a| a.as_ref());
+            self.$field.vecs.retain(|v| v.arch() != arch);
+            self.set_provenance(stringify!($field), arch, Provenance::Cleared);
+        }
+    };
+}
+
+impl Srcinfo {
+    /// Sets pkgver and resets pkgrel to 1.
+    ///
+    /// The epoch is left as is. Like makepkg the pkgver must not be empty and must not contain
+    /// whitespace, '/', ':' or '-' as those could not be read back as the same version.
+    /// Returns [`ErrorKind::EmptyValue`] or [`ErrorKind::InvalidVersion`] and leaves the
+    /// Srcinfo unchanged otherwise.
+    pub fn set_version<S: Into<String>>(&mut self, pkgver: S) -> Result<(), Error> {
+        let pkgver = pkgver.into();
+        if pkgver.is_empty() {
+            return Err(ErrorKind::EmptyValue("pkgver".to_string()).into());
+        }
+        if pkgver.contains(|c: char| c.is_whitespace() || matches!(c, '/' | ':' | '-')) {
+            return Err(ErrorKind::InvalidVersion(pkgver).into());
+        }
+
+        self.base.pkgver = pkgver;
+        self.base.pkgrel = "1".to_string();
+        Ok(())
+    }
+
+    /// Increments pkgrel.
+    ///
+    /// Any sub release is dropped so "1.1" becomes "2". Returns [`ErrorKind::InvalidVersion`]
+    /// holding the pkgrel, which is left as is, if the pkgrel is not a number.
+    pub fn bump_pkgrel(&mut self) -> Result<(), Error> {
+        let rel = self.base.pkgrel.split('.').next().unwrap_or_default();
+        let rel = rel
+            .parse::<u64>()
+            .map_err(|_| ErrorKind::InvalidVersion(self.base.pkgrel.clone()))?;
+        self.base.pkgrel = (rel + 1).to_string();
+        Ok(())
+    }
+
+    /// Gets a mutable reference to the package with the given name.
+    ///
+    /// Edit the package with the [`Package`] methods such as [`Package::push_depends`] so that
+    /// the edited fields are no longer inherited and are kept by [`Srcinfo::edit_global`].
+    pub fn pkg_mut<S: AsRef<str>>(&mut self, name: S) -> Option<&mut Package> {
+        self.pkgs.iter_mut().find(|p| p.pkgname == name.as_ref())
+    }
+
+    /// Adds a package to the end of the Srcinfo.
+    ///
+    /// Fields that the package does not set are inherited from [`Srcinfo::pkg`], the same as
+    /// [`SrcinfoBuilder::package`](crate::SrcinfoBuilder::package).
+    ///
+    /// Returns [`ErrorKind::DuplicatePkgname`] if a package with the same name already exists.
+    pub fn add_package(&mut self, pkg: PackageBuilder) -> Result<&mut Package, Error> {
+        let pkg = pkg.merge(&self.pkg);
+        if self.pkgs.iter().any(|p| p.pkgname == pkg.pkgname) {
+            return Err(ErrorKind::DuplicatePkgname(pkg.pkgname).into());
+        }
+
+        self.pkgs.push(pkg);
+        Ok(self.pkgs.last_mut().unwrap())
+    }
+
+    /// Removes the package with the given name.
+    ///
+    /// Removing the last package leaves the Srcinfo without a pkgname, which
+    /// [`Srcinfo::validate`] reports.
+    pub fn remove_package<S: AsRef<str>>(&mut self, name: S) -> Option<Package> {
+        let pos = self.pkgs.iter().position(|p| p.pkgname == name.as_ref())?;
+        Some(self.pkgs.remove(pos))
+    }
+
+    /// Edits the global package fields and updates the packages that inherit them.
+    ///
+    /// Every package field that is [`Provenance::Inherited`](crate::Provenance::Inherited) is
+    /// set to the new value, the same way the parser fills in the fields a package section does
+    /// not set. Architecture specific fields are handled per architecture. Package fields that
+    /// were edited with the [`Package`] methods such as [`Package::push_depends`] are kept, a
+    /// field that was assigned directly should be marked with [`Package::set_provenance`].
+    ///
+    /// ```
+    /// # use srcinfo::Error;
+    /// use srcinfo::Srcinfo;
+    ///
+    /// # fn test() -> Result<(), Error> {
+    /// let mut srcinfo: Srcinfo = "
+    /// pkgbase = example
+    /// pkgver = 1.5.0
+    /// pkgrel = 5
+    /// depends = foo
+    ///
+    /// pkgname = example
+    ///
+    /// pkgname = example-docs
+    /// depends =".parse()?;
+    ///
+    /// srcinfo.edit_global(|pkg| pkg.depends.push(None::<String>, "bar"));
+    ///
+    /// let depends = srcinfo.pkg("example").unwrap().depends.all().collect::<Vec<_>>();
+    /// assert_eq!(depends, vec!["foo", "bar"]);
+    /// assert!(srcinfo.pkg("example-docs").unwrap().depends.is_empty());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn edit_global<F: FnOnce(&mut Package)>(&mut self, f: F) {
+        f(&mut self.pkg);
+        for pkg in &mut self.pkgs {
+            inherit_fields!(pkg, &self.pkg);
+        }
+    }
+}
+
+impl Package {
+    edit_option!(set_pkgdesc, clear_pkgdesc, pkgdesc);
+    edit_vec!(
+        push_architecture,
+        remove_architecture,
+        clear_architectures,
+        arch
+    );
+    edit_option!(set_url, clear_url, url);
+    edit_vec!(push_license, remove_license, clear_license, license);
+    edit_vec!(push_groups, remove_groups, clear_groups, groups);
+    edit_arch_vecs!(push_depends, remove_depends, clear_depends, depends);
+    edit_arch_vecs!(
+        push_optdepends,
+        remove_optdepends,
+        clear_optdepends,
+        optdepends
+    );
+    edit_arch_vecs!(push_provides, remove_provides, clear_provides, provides);
+    edit_arch_vecs!(push_conflicts, remove_conflicts, clear_conflicts, conflicts);
+    edit_arch_vecs!(push_replaces, remove_replaces, clear_replaces, replaces);
+    edit_vec!(push_backup, remove_backup, clear_backup, backup);
+    edit_vec!(push_options, remove_options, clear_options, options);
+    edit_option!(set_install, clear_install, install);
+    edit_option!(set_changelog, clear_changelog, changelog);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_version() {
+        let mut srcinfo = Srcinfo::from_path("tests/srcinfo/libc++").unwrap();
+        srcinfo.base.pkgrel = "3.1".to_string();
+        srcinfo.bump_pkgrel().unwrap();
+        assert_eq!(srcinfo.pkgrel(), "4");
+        srcinfo.bump_pkgrel().unwrap();
+        assert_eq!(srcinfo.pkgrel(), "5");
+
+        srcinfo.set_version("7.0.0").unwrap();
+        assert_eq!(srcinfo.version(), "7.0.0-1");
+
+        for pkgver in ["7.0.0-2", "1:7.0.0", "7.0 0", "7/0"] {
+            let err = srcinfo.set_version(pkgver).unwrap_err();
+            assert!(matches!(err.kind, ErrorKind::InvalidVersion(v) if v == pkgver));
+        }
+        let err = srcinfo.set_version("").unwrap_err();
+        assert!(matches!(err.kind, ErrorKind::EmptyValue(k) if k == "pkgver"));
+        assert_eq!(srcinfo.version(), "7.0.0-1");
+
+        srcinfo.base.pkgrel = "foo".to_string();
+        let err = srcinfo.bump_pkgrel().unwrap_err();
+        assert!(matches!(err.kind, ErrorKind::InvalidVersion(v) if v == "foo"));
+        assert_eq!(srcinfo.pkgrel(), "foo");
+    }
+
+    #[test]
+    fn test_packages() {
+        let mut srcinfo = Srcinfo::from_path("tests/srcinfo/libc++").unwrap();
+
+        let pkg = srcinfo
+            .add_package(PackageBuilder::new("libc++-docs").clear_depends())
+            .unwrap();
+        assert_eq!(pkg.url.as_deref(), Some("https://libcxx.llvm.org/"));
+        assert!(pkg.depends.is_empty());
+
+        let err = srcinfo.add_package(PackageBuilder::new("libc++abi"));
+        assert!(
+            matches!(err.unwrap_err().kind, ErrorKind::DuplicatePkgname(p) if p == "libc++abi")
+        );
+
+        let pkg = srcinfo.remove_package("libc++-docs").unwrap();
+        assert_eq!(pkg.pkgname, "libc++-docs");
+        assert!(srcinfo.remove_package("libc++-docs").is_none());
+        assert_eq!(
+            srcinfo.to_string(),
+            include_str!("../tests/srcinfo/libc++")
+                .parse::<Srcinfo>()
+                .unwrap()
+                .to_string()
+        );
+    }
+
+    #[test]
+    fn test_edit_global() {
+        let mut srcinfo: Srcinfo = "
+pkgbase = a
+pkgver = 1
+pkgrel = 1
+arch = x86_64
+arch = i686
+pkgdesc = desc
+depends = foo
+depends_x86_64 = bar
+
+pkgname = a
+
+pkgname = b
+pkgdesc = b desc
+depends_x86_64 = baz
+
+pkgname = c
+depends =
+depends_x86_64 ="
+            .parse()
+            .unwrap();
+
+        srcinfo.edit_global(|pkg| {
+            pkg.pkgdesc = Some("new desc".to_string());
+            pkg.depends.remove(None::<&str>, "foo");
+            pkg.depends.push(Some("x86_64"), "qux");
+            pkg.depends.push(Some("i686"), "lib32");
+        });
+
+        let a = srcinfo.pkg("a").unwrap();
+        assert_eq!(a.pkgdesc.as_deref(), Some("new desc"));
+        assert_eq!(
+            a.depends.all().collect::<Vec<_>>(),
+            vec!["bar", "qux", "lib32"]
+        );
+
+        let b = srcinfo.pkg("b").unwrap();
+        assert_eq!(b.pkgdesc.as_deref(), Some("b desc"));
+        assert_eq!(b.depends.arch("x86_64").collect::<Vec<_>>(), vec!["baz"]);
+        assert_eq!(b.depends.arch("i686").collect::<Vec<_>>(), vec!["lib32"]);
+
+        let c = srcinfo.pkg("c").unwrap();
+        assert_eq!(
+            c.depends.arch("x86_64").collect::<Vec<_>>(),
+            Vec::<&str>::new()
+        );
+        assert_eq!(c.depends.arch("i686").collect::<Vec<_>>(), vec!["lib32"]);
+
+        assert_eq!(srcinfo.to_string().parse::<Srcinfo>().unwrap(), srcinfo);
+    }
+
+    #[test]
+    fn test_edit_package() {
+        let mut srcinfo: Srcinfo = "
+pkgbase = a
+pkgver = 1
+pkgrel = 1
+arch = x86_64
+pkgdesc = desc
+license = MIT
+depends = foo
+depends_x86_64 = bar
+
+pkgname = a
+
+pkgname = b"
+            .parse()
+            .unwrap();
+
+        let a = srcinfo.pkg_mut("a").unwrap();
+        a.push_depends(None::<&str>, "baz");
+        assert!(a.remove_depends(Some("x86_64"), "bar"));
+        assert!(!a.remove_depends(Some("x86_64"), "bar"));
+        a.set_pkgdesc("a desc");
+        a.clear_license();
+
+        let b = srcinfo.pkg_mut("b").unwrap();
+        assert!(b.remove_license("MIT"));
+        b.clear_depends(None::<&str>);
+
+        srcinfo.edit_global(|pkg| {
+            pkg.pkgdesc = Some("new desc".to_string());
+            pkg.license = vec!["GPL".to_string()];
+            pkg.depends.push(None::<&str>, "qux");
+            pkg.depends.push(Some("x86_64"), "quux");
+        });
+
+        let a = srcinfo.pkg("a").unwrap();
+        assert_eq!(a.pkgdesc.as_deref(), Some("a desc"));
+        assert!(a.license.is_empty());
+        assert_eq!(
+            a.depends.arch("x86_64").collect::<Vec<_>>(),
+            vec!["foo", "baz"]
+        );
+        assert_eq!(a.provenance("depends", Some("x86_64")), Provenance::Cleared);
+
+        let b = srcinfo.pkg("b").unwrap();
+        assert_eq!(b.pkgdesc.as_deref(), Some("new desc"));
+        assert!(b.license.is_empty());
+        assert_eq!(
+            b.depends.arch("x86_64").collect::<Vec<_>>(),
+            vec!["bar", "quux"]
+        );
+
+        assert_eq!(srcinfo.to_string().parse::<Srcinfo>().unwrap(), srcinfo);
+    }
+}
@@ -13,15 +13,46 @@ pub struct ErrorLine {
 
 /// A list of possible errors that may occur when parsing a .SRCINFO.
 ///
-/// Variants that hold a string hold the key that caused the error.
+/// Variants that hold a string hold the key that caused the error. DuplicatePkgname holds the
+/// pkgname.
 ///
 /// UndeclaredArch holds the key that caused the error and the architecture.
 ///
+/// UndeclaredPackageArch holds the pkgname and the architecture.
+///
+/// ChecksumCount holds the checksum algorithm, the architecture, the number of sources and the
+/// number of checksums.
+///
+/// MissingChecksums holds the architecture of the source field without checksums.
+///
+/// MultipleSrcinfo holds the paths of the first two .SRCINFO files found.
+///
+/// InvalidUtf8 holds the byte offset of the first invalid byte in the input.
+///
+/// InvalidVersion holds the version that could not be parsed.
+///
+/// InvalidDependency holds the dependency that could not be parsed.
+///
 /// IoError holds the underlying IO::Error.
+///
+/// New kinds of errors may be added in minor releases.
 #[derive(Debug)]
+#[non_exhaustive]
 pub enum ErrorKind {
     /// pkgbase was specified more than once
     DuplicatePkgbase,
+    /// A pkgname section was declared more than once
+    ///
+    /// Only returned when parsing with [`ParseOptions::strict`](crate::ParseOptions::strict).
+    DuplicatePkgname(String),
+    /// A field that only holds one value was specified more than once in the same section
+    ///
+    /// Only returned when parsing with [`ParseOptions::strict`](crate::ParseOptions::strict).
+    DuplicateField(String),
+    /// A key that is not known to makepkg was used
+    ///
+    /// Only returned when parsing with [`ParseOptions::strict`](crate::ParseOptions::strict).
+    UnknownKey(String),
     /// An architecture specific field was declared using an architecture
     /// that has not been declared
     UndeclaredArch(String, String),
@@ -40,6 +71,37 @@ pub enum ErrorKind {
     /// An architecture specific field was declared on a field that can not
     /// be architecture specific
     NotArchSpecific(String),
+    /// A package declared an architecture that the pkgbase did not declare
+    ///
+    /// Only returned by [`Srcinfo::validate`](crate::Srcinfo::validate).
+    UndeclaredPackageArch(String, String),
+    /// A checksum field does not have the same amount of values as the matching source field
+    ///
+    /// Only returned by [`Srcinfo::validate`](crate::Srcinfo::validate) and
+    /// [`PackageBase::check_checksums`](crate::PackageBase::check_checksums).
+    ChecksumCount(String, Option<String>, usize, usize),
+    /// A source field has no matching checksum field for any algorithm
+    ///
+    /// Only returned by [`Srcinfo::validate`](crate::Srcinfo::validate) and
+    /// [`PackageBase::check_checksums`](crate::PackageBase::check_checksums).
+    MissingChecksums(Option<String>),
+    /// An archive does not contain a .SRCINFO
+    MissingSrcinfo,
+    /// An archive contains more than one .SRCINFO
+    MultipleSrcinfo(String, String),
+    /// The input is not valid UTF-8
+    InvalidUtf8(usize),
+    /// A version does not have a pkgver, or a pkgver or pkgrel is invalid
+    ///
+    /// Only returned when parsing a [`Version`](crate::Version), by
+    /// [`Srcinfo::bump_pkgrel`](crate::Srcinfo::bump_pkgrel) when the pkgrel is not a number
+    /// and by [`Srcinfo::set_version`](crate::Srcinfo::set_version) when the pkgver is invalid.
+    InvalidVersion(String),
+    /// A dependency has an empty name or an invalid version
+    ///
+    /// Only returned when parsing a [`Dependency`](crate::Dependency) or an
+    /// [`OptDepend`](crate::OptDepend).
+    InvalidDependency(String),
     /// An IoError occurred
     IoError(io::Error),
 }
@@ -48,6 +110,9 @@ impl fmt::Display for ErrorKind {
     fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
         match self {
             ErrorKind::DuplicatePkgbase => write!(fmt, "pkgbase already set"),
+            ErrorKind::DuplicatePkgname(p) => write!(fmt, "pkgname '{}' already declared", p),
+            ErrorKind::DuplicateField(k) => write!(fmt, "key '{}' already set", k),
+            ErrorKind::UnknownKey(k) => write!(fmt, "unknown key '{}'", k),
             ErrorKind::UndeclaredArch(k, a) => {
                 write!(fmt, "undeclared architecture '{}' in key '{}'", a, k)
             }
@@ -59,6 +124,26 @@ impl fmt::Display for ErrorKind {
             ErrorKind::NotArchSpecific(k) => {
                 write!(fmt, "key '{}' can not be architecture specific", k)
             }
+            ErrorKind::UndeclaredPackageArch(p, a) => write!(
+                fmt,
+                "package '{}' uses architecture '{}' which is not declared by the pkgbase",
+                p, a
+            ),
+            ErrorKind::ChecksumCount(k, a, e, f) => match a {
+                Some(a) => write!(fmt, "key '{}_{}' has {} values, expected {}", k, a, f, e),
+                None => write!(fmt, "key '{}' has {} values, expected {}", k, f, e),
+            },
+            ErrorKind::MissingChecksums(a) => match a {
+                Some(a) => write!(fmt, "key 'source_{}' has no checksums", a),
+                None => write!(fmt, "key 'source' has no checksums"),
+            },
+            ErrorKind::MissingSrcinfo => write!(fmt, "no .SRCINFO found"),
+            ErrorKind::MultipleSrcinfo(a, b) => {
+                write!(fmt, "multiple .SRCINFO files found: '{}' and '{}'", a, b)
+            }
+            ErrorKind::InvalidUtf8(o) => write!(fmt, "invalid utf-8 at byte {}", o),
+            ErrorKind::InvalidVersion(v) => write!(fmt, "invalid version '{}'", v),
+            ErrorKind::InvalidDependency(d) => write!(fmt, "invalid dependency '{}'", d),
             ErrorKind::IoError(err) => err.fmt(fmt),
         }
     }
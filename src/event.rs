@@ -0,0 +1,270 @@
+use crate::error::{Error, ErrorKind};
+use crate::syntax::LineKind;
+
+// Splits a "key = pair" that was tokenized by LineKind
+pub(crate) fn split_pair(kind: LineKind<'_>) -> Result<(&str, Option<&str>), ErrorKind> {
+    match kind {
+        LineKind::Field { key: "", .. } => Err(ErrorKind::EmptyKey),
+        LineKind::Field { key, value } => Ok((key, empty_to_none(value))),
+        LineKind::Invalid(line) => Err(ErrorKind::EmptyValue(line.to_string())),
+        // not a field, callers handle these before splitting
+        LineKind::Blank | LineKind::Comment(_) => Err(ErrorKind::EmptyKey),
+    }
+}
+
+// splits depends_foo to ("depends", Some("foo"))
+pub(crate) fn split_key_arch(s: &str) -> (&str, Option<&str>) {
+    let mut split = s.splitn(2, '_');
+    let key = split.next().unwrap();
+    let arch = split.next();
+    (key, arch)
+}
+
+fn empty_to_none(s: &str) -> Option<&str> {
+    (!s.is_empty()).then_some(s)
+}
+
+/// A single item read from a .SRCINFO.
+///
+/// Events only describe what is written in the file. They do not check that keys are
+/// used in the right section or that architectures have been declared. [`Srcinfo`](crate::Srcinfo)
+/// performs those checks when it is built from the events.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum Event<'a> {
+    /// A comment, holds the text after the '#' with whitespace removed
+    Comment(&'a str),
+    /// A "pkgbase = value" line
+    Pkgbase(&'a str),
+    /// A "pkgname = value" line, starting a new package section
+    Pkgname(&'a str),
+    /// A "key = value" line
+    Field {
+        /// The key without the architecture suffix
+        key: &'a str,
+        /// The architecture suffix of the key
+        arch: Option<&'a str>,
+        /// The value
+        value: &'a str,
+    },
+    /// A "key =" line without a value
+    ///
+    /// Inside of a pkgname section this clears the value the package would otherwise
+    /// inherit from the pkgbase section. An empty `pkgbase` or `pkgname` is also read as an
+    /// EmptyOverride, [`Srcinfo`](crate::Srcinfo) rejects it.
+    EmptyOverride {
+        /// The key without the architecture suffix
+        key: &'a str,
+        /// The architecture suffix of the key
+        arch: Option<&'a str>,
+    },
+}
+
+impl<'a> Event<'a> {
+    /// Reads the event from a single line.
+    ///
+    /// Blank lines do not produce an event.
+    pub fn from_line(line: &'a str) -> Result<Option<Event<'a>>, ErrorKind> {
+        let (key, value) = match LineKind::new(line) {
+            LineKind::Blank => return Ok(None),
+            LineKind::Comment(comment) => return Ok(Some(Event::Comment(comment.trim()))),
+            kind => split_pair(kind)?,
+        };
+
+        let event = match (key, value) {
+            ("pkgbase", Some(value)) => Event::Pkgbase(value),
+            ("pkgname", Some(value)) => Event::Pkgname(value),
+            (key, value) => {
+                let (key, arch) = split_key_arch(key);
+                match value {
+                    Some(value) => Event::Field { key, arch, value },
+                    None => Event::EmptyOverride { key, arch },
+                }
+            }
+        };
+
+        Ok(Some(event))
+    }
+}
+
+/// A streaming iterator over the [`Event`]s of a .SRCINFO.
+///
+/// Unlike parsing into a [`Srcinfo`](crate::Srcinfo) nothing is allocated or merged and reading can
+/// be stopped at any point.
+///
+/// ```
+/// # use srcinfo::Error;
+/// use srcinfo::{Event, Events};
+///
+/// # fn test() -> Result<(), Error> {
+/// let input = "
+/// pkgbase = example
+/// pkgver = 1.5.0
+/// pkgrel = 5
+///
+/// pkgname = example
+/// depends = foo
+///
+/// pkgname = example2";
+///
+/// let mut names = Vec::new();
+/// for event in Events::new(input) {
+///     match event? {
+///         Event::Pkgname(name) => names.push(name),
+///         Event::Field { key: "depends", .. } => break,
+///         _ => (),
+///     }
+/// }
+///
+/// assert_eq!(names, vec!["example"]);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct Events<'a> {
+    lines: std::str::Lines<'a>,
+    line: usize,
+}
+
+impl<'a> Events<'a> {
+    /// Creates an iterator over the events in a string.
+    pub fn new(s: &'a str) -> Events<'a> {
+        Events {
+            lines: s.lines(),
+            line: 0,
+        }
+    }
+
+    /// The line number of the most recently read line, starting at 1
+    pub fn line(&self) -> usize {
+        self.line
+    }
+}
+
+impl<'a> Iterator for Events<'a> {
+    type Item = Result<Event<'a>, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        for line in self.lines.by_ref() {
+            self.line += 1;
+
+            match Event::from_line(line) {
+                Ok(Some(event)) => return Some(Ok(event)),
+                Ok(None) => (),
+                Err(e) => return Some(Err(Error::new(e, line.trim(), self.line))),
+            }
+        }
+
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_pair() {
+        assert_eq!(split_pair(LineKind::new("a=b")).unwrap(), ("a", Some("b")));
+        assert_eq!(
+            split_pair(LineKind::new("a==b")).unwrap(),
+            ("a", Some("=b"))
+        );
+        assert_eq!(split_pair(LineKind::new("a= b")).unwrap(), ("a", Some("b")));
+        assert_eq!(split_pair(LineKind::new("a =b")).unwrap(), ("a", Some("b")));
+        assert_eq!(
+            split_pair(LineKind::new("a = b")).unwrap(),
+            ("a", Some("b"))
+        );
+        assert_eq!(
+            split_pair(LineKind::new(" a = b ")).unwrap(),
+            ("a", Some("b"))
+        );
+        assert_eq!(
+            split_pair(LineKind::new("\ta\t= b")).unwrap(),
+            ("a", Some("b"))
+        );
+        assert_eq!(split_pair(LineKind::new("a=")).unwrap(), ("a", None));
+        assert_eq!(split_pair(LineKind::new(" a =")).unwrap(), ("a", None));
+
+        let err = split_pair(LineKind::new("a")).unwrap_err();
+        match err {
+            ErrorKind::EmptyValue(ref key) => assert_eq!(key, "a"),
+            _ => panic!("{:?}", err),
+        }
+
+        assert!(split_pair(LineKind::new("=b")).is_err());
+    }
+
+    #[test]
+    fn test_split_key_arch() {
+        assert_eq!(split_key_arch("a_b"), ("a", Some("b")));
+        assert_eq!(split_key_arch("a_b_c"), ("a", Some("b_c")));
+        assert_eq!(split_key_arch("a"), ("a", None));
+    }
+
+    #[test]
+    fn test_events() {
+        let input = include_str!("../tests/srcinfo/arch_in_package");
+        let events = Events::new(input).collect::<Result<Vec<_>, _>>().unwrap();
+
+        assert_eq!(events.len(), 9);
+        assert_eq!(events[0], Event::Pkgbase("test"));
+        assert_eq!(
+            events[4],
+            Event::Field {
+                key: "arch",
+                arch: None,
+                value: "x86_64"
+            }
+        );
+        assert_eq!(events[6], Event::Pkgname("test"));
+        assert_eq!(
+            events[8],
+            Event::Field {
+                key: "depends",
+                arch: Some("x86_64"),
+                value: "fwupd"
+            }
+        );
+
+        let input = "# comment\npkgbase = a\n\npkgname = a\n\tdepends_x86_64 =\n";
+        let events = Events::new(input).collect::<Result<Vec<_>, _>>().unwrap();
+        assert_eq!(
+            events,
+            vec![
+                Event::Comment("comment"),
+                Event::Pkgbase("a"),
+                Event::Pkgname("a"),
+                Event::EmptyOverride {
+                    key: "depends",
+                    arch: Some("x86_64")
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_events_empty_pkgname() {
+        assert_eq!(
+            Event::from_line("pkgname =").unwrap(),
+            Some(Event::EmptyOverride {
+                key: "pkgname",
+                arch: None
+            })
+        );
+    }
+
+    #[test]
+    fn test_events_error() {
+        let mut events = Events::new(include_str!("../tests/srcinfo/no_key"));
+        assert_eq!(events.by_ref().take(4).filter(Result::is_ok).count(), 4);
+
+        let err = events.next().unwrap().unwrap_err();
+        assert_eq!(events.line(), 5);
+        assert_eq!(err.line.unwrap().number, 5);
+        assert!(matches!(err.kind, ErrorKind::EmptyKey));
+
+        assert_eq!(events.next().unwrap().unwrap(), Event::Pkgname("foo"));
+        assert!(events.next().is_none());
+    }
+}
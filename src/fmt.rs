@@ -1,6 +1,9 @@
+use std::collections::BTreeMap;
 use std::fmt::{Display, Formatter, Result as FmtResult};
 
-use crate::{ArchVec, ArchVecs, Package, Srcinfo};
+use crate::{
+    ArchVec, ArchVecs, Dependency, Generator, Op, OptDepend, Package, Provenance, Srcinfo, Version,
+};
 
 fn write_val_arch(w: &mut Formatter<'_>, key: &str, arch: Option<&str>, value: &str) -> FmtResult {
     match arch {
@@ -27,6 +30,13 @@ fn write_arch_vecs(w: &mut Formatter<'_>, key: &str, values: &ArchVecs) -> FmtRe
     Ok(())
 }
 
+fn write_extra(w: &mut Formatter<'_>, extra: &BTreeMap<String, ArchVecs>) -> FmtResult {
+    for (key, values) in extra {
+        write_arch_vecs(w, key, values)?;
+    }
+    Ok(())
+}
+
 fn write_arr<S: AsRef<str>>(
     w: &mut Formatter<'_>,
     key: &str,
@@ -38,15 +48,38 @@ fn write_arr<S: AsRef<str>>(
     Ok(())
 }
 
-fn write_pkg_val(w: &mut Formatter<'_>, k: &str, v: Option<&str>, base: Option<&str>) -> FmtResult {
-    if v != base {
+// A field is written when the package section set or cleared it. A package without any
+// provenance was built by hand rather than parsed or edited through the Package methods, so
+// for those a field is written when it differs from the pkgbase instead.
+fn is_explicit(pkg: &Package, key: &str, arch: Option<&str>, differs: bool) -> bool {
+    if pkg.provenance.is_empty() {
+        differs
+    } else {
+        pkg.provenance(key, arch) != Provenance::Inherited
+    }
+}
+
+fn write_pkg_val(
+    w: &mut Formatter<'_>,
+    pkg: &Package,
+    k: &str,
+    v: Option<&str>,
+    base: Option<&str>,
+) -> FmtResult {
+    if is_explicit(pkg, k, None, v != base) {
         write_val(w, k, v.unwrap_or_default())?;
     }
     Ok(())
 }
 
-fn write_pkg_arr(w: &mut Formatter<'_>, k: &str, v: &[String], base: &[String]) -> FmtResult {
-    match (v != base, v.is_empty()) {
+fn write_pkg_arr(
+    w: &mut Formatter<'_>,
+    pkg: &Package,
+    k: &str,
+    v: &[String],
+    base: &[String],
+) -> FmtResult {
+    match (is_explicit(pkg, k, None, v != base), v.is_empty()) {
         (true, true) => write_val(w, k, ""),
         (true, false) => write_arr(w, k, v),
         _ => Ok(()),
@@ -55,27 +88,87 @@ fn write_pkg_arr(w: &mut Formatter<'_>, k: &str, v: &[String], base: &[String])
 
 fn write_pkg_arch_vecs(
     w: &mut Formatter<'_>,
+    pkg: &Package,
     key: &str,
     values: &ArchVecs,
     base: &ArchVecs,
 ) -> FmtResult {
     for value in values {
-        match base.get(value.arch()) {
-            Some(base) if base != value => write_arch_vec(w, key, value)?,
-            None => write_arch_vec(w, key, value)?,
-            _ => (),
+        if is_explicit(
+            pkg,
+            key,
+            value.arch(),
+            base.get(value.arch()) != Some(value),
+        ) {
+            write_arch_vec(w, key, value)?;
         }
     }
 
-    for base in base {
-        if values.get(base.arch()).is_none() {
-            write_val_arch(w, key, base.arch(), "")?;
+    let explicit = pkg
+        .provenance
+        .iter()
+        .filter(|(k, _, _)| k == key)
+        .map(|(_, a, _)| a.as_deref());
+    let mut missing = Vec::new();
+
+    for arch in base.iter().map(|v| v.arch()).chain(explicit) {
+        if values.get(arch).is_none()
+            && is_explicit(pkg, key, arch, true)
+            && !missing.contains(&arch)
+        {
+            missing.push(arch);
+            write_val_arch(w, key, arch, "")?;
         }
     }
 
     Ok(())
 }
 
+impl Display for Generator {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        write!(f, "Generated by {} {}", self.tool, self.version)
+    }
+}
+
+impl Display for Version {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        if let Some(epoch) = &self.epoch {
+            write!(f, "{}:", epoch)?;
+        }
+        write!(f, "{}", self.pkgver)?;
+        if let Some(pkgrel) = &self.pkgrel {
+            write!(f, "-{}", pkgrel)?;
+        }
+        Ok(())
+    }
+}
+
+impl Display for Op {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        f.write_str(self.as_str())
+    }
+}
+
+impl Display for Dependency {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        write!(f, "{}", self.name)?;
+        if let Some((op, version)) = &self.constraint {
+            write!(f, "{}{}", op, version)?;
+        }
+        Ok(())
+    }
+}
+
+impl Display for OptDepend {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        write!(f, "{}", self.dep)?;
+        if let Some(description) = &self.description {
+            write!(f, ": {}", description)?;
+        }
+        Ok(())
+    }
+}
+
 impl Display for Srcinfo {
     fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
         self.write_all(f)
@@ -92,20 +185,21 @@ impl Srcinfo {
 
     fn write_pkg(&self, pkg: &Package, w: &mut Formatter<'_>) -> FmtResult {
         write!(w, "\n\npkgname = {}", pkg.pkgname())?;
-        write_pkg_val(w, "pkgdesc", pkg.pkgdesc(), self.pkgdesc())?;
-        write_pkg_val(w, "url", pkg.url(), self.url())?;
-        write_pkg_val(w, "install", pkg.install(), self.install())?;
-        write_pkg_val(w, "changelog", pkg.changelog(), self.changelog())?;
-        write_pkg_arr(w, "arch", pkg.arch(), self.arch())?;
-        write_pkg_arr(w, "groups", pkg.groups(), self.groups())?;
-        write_pkg_arr(w, "license", pkg.license(), self.license())?;
-        write_pkg_arch_vecs(w, "depends", pkg.depends(), self.depends())?;
-        write_pkg_arch_vecs(w, "optdepends", pkg.optdepends(), self.optdepends())?;
-        write_pkg_arch_vecs(w, "provides", pkg.provides(), self.provides())?;
-        write_pkg_arch_vecs(w, "conflicts", pkg.conflicts(), self.conflicts())?;
-        write_pkg_arch_vecs(w, "replaces", pkg.replaces(), self.replaces())?;
-        write_pkg_arr(w, "options", pkg.options(), self.options())?;
-        write_pkg_arr(w, "backup", pkg.backup(), self.backup())?;
+        write_pkg_val(w, pkg, "pkgdesc", pkg.pkgdesc(), self.pkgdesc())?;
+        write_pkg_val(w, pkg, "url", pkg.url(), self.url())?;
+        write_pkg_val(w, pkg, "install", pkg.install(), self.install())?;
+        write_pkg_val(w, pkg, "changelog", pkg.changelog(), self.changelog())?;
+        write_pkg_arr(w, pkg, "arch", pkg.arch(), self.arch())?;
+        write_pkg_arr(w, pkg, "groups", pkg.groups(), self.groups())?;
+        write_pkg_arr(w, pkg, "license", pkg.license(), self.license())?;
+        write_pkg_arch_vecs(w, pkg, "depends", pkg.depends(), self.depends())?;
+        write_pkg_arch_vecs(w, pkg, "optdepends", pkg.optdepends(), self.optdepends())?;
+        write_pkg_arch_vecs(w, pkg, "provides", pkg.provides(), self.provides())?;
+        write_pkg_arch_vecs(w, pkg, "conflicts", pkg.conflicts(), self.conflicts())?;
+        write_pkg_arch_vecs(w, pkg, "replaces", pkg.replaces(), self.replaces())?;
+        write_pkg_arr(w, pkg, "options", pkg.options(), self.options())?;
+        write_pkg_arr(w, pkg, "backup", pkg.backup(), self.backup())?;
+        write_extra(w, pkg.extra())?;
         Ok(())
     }
 
@@ -134,6 +228,7 @@ impl Srcinfo {
         write_arr(w, "backup", self.backup())?;
         write_arch_vecs(w, "source", self.source())?;
         write_arr(w, "validpgpkeys", self.valid_pgp_keys())?;
+        write_arch_vecs(w, "cksums", self.cksums())?;
         write_arch_vecs(w, "md5sums", self.md5sums())?;
         write_arch_vecs(w, "sha1sums", self.sha1sums())?;
         write_arch_vecs(w, "sha224sums", self.sha224sums())?;
@@ -141,6 +236,7 @@ impl Srcinfo {
         write_arch_vecs(w, "sha384sums", self.sha384sums())?;
         write_arch_vecs(w, "sha512sums", self.sha512sums())?;
         write_arch_vecs(w, "b2sums", self.b2sums())?;
+        write_extra(w, self.extra())?;
 
         for pkg in &self.pkgs {
             self.write_pkg(pkg, w)?;
@@ -173,4 +269,104 @@ mod tests {
             assert_eq!(original, srcinfo);
         }
     }
+
+    #[test]
+    fn test_fmt_generator() {
+        let mut srcinfo = include_str!("../tests/srcinfo/libc++")
+            .parse::<Srcinfo>()
+            .unwrap();
+        srcinfo.set_generator(Generator::new("makepkg", "6.0.2"));
+
+        let output = srcinfo.to_string();
+        assert!(output.starts_with("# Generated by makepkg 6.0.2\npkgbase = libc++\n"));
+        assert_eq!(output.parse::<Srcinfo>().unwrap(), srcinfo);
+
+        let srcinfo = include_str!("../tests/srcinfo/good/als-controller")
+            .parse::<Srcinfo>()
+            .unwrap();
+        let generator = srcinfo.generator().unwrap();
+        assert_eq!(generator.to_string(), "Generated by mksrcinfo v8");
+        assert!(
+            srcinfo
+                .to_string()
+                .starts_with("# Generated by mksrcinfo v8\n")
+        );
+    }
+
+    #[test]
+    fn test_fmt_extra() {
+        let original = include_str!("../tests/srcinfo/unknown_keys");
+        let srcinfo = original.parse::<Srcinfo>().unwrap();
+        let output = srcinfo.to_string();
+
+        for line in [
+            "\tfoo = a",
+            "\tfoo = b",
+            "\tfoo_x86_64 = c",
+            "\txdata = pkgtype=debug",
+        ] {
+            assert_eq!(output.lines().filter(|l| *l == line).count(), 1, "{}", line);
+        }
+        assert!(output.ends_with("pkgname = bar\n\tbar = 1"));
+        assert_eq!(output.parse::<Srcinfo>().unwrap(), srcinfo);
+    }
+
+    #[test]
+    fn test_fmt_provenance() {
+        let original = "pkgbase = a\n\tpkgver = 1\n\tpkgrel = 1\n\tarch = x86_64\n\tdepends = foo\n\
+                        \tdepends_x86_64 = bar\n\npkgname = a\n\turl = \n\tdepends = foo\n\
+                        \tdepends_x86_64 = ";
+        let mut srcinfo = original.parse::<Srcinfo>().unwrap();
+        assert_eq!(srcinfo.to_string(), original);
+
+        srcinfo.pkgs[0].push_depends(None::<&str>, "baz");
+        srcinfo.pkgs[0].set_pkgdesc("edited");
+        let output = srcinfo.to_string();
+        assert!(output.ends_with(
+            "\tpkgdesc = edited\n\turl = \n\tdepends = foo\n\tdepends = baz\n\tdepends_x86_64 = "
+        ));
+
+        // without a provenance the package was built by hand
+        let pkg = Package {
+            pkgname: "b".to_string(),
+            pkgdesc: Some("b desc".to_string()),
+            depends: srcinfo.pkg.depends.clone(),
+            ..Default::default()
+        };
+        srcinfo.pkgs = vec![pkg];
+        let output = srcinfo.to_string();
+        assert!(
+            output.ends_with("pkgname = b\n\tpkgdesc = b desc\n\tarch = "),
+            "{}",
+            output
+        );
+    }
+
+    #[test]
+    fn test_fmt_overridden_equal() {
+        let original = "pkgbase = a\n\tpkgdesc = desc\n\tpkgver = 1\n\tpkgrel = 1\n\tarch = x86_64\n\
+                        \tdepends_x86_64 = foo\n\npkgname = a\n\tpkgdesc = desc\n\
+                        \tdepends_x86_64 = foo\n\npkgname = b";
+        let srcinfo = original.parse::<Srcinfo>().unwrap();
+        assert_eq!(srcinfo.to_string(), original);
+
+        let reparsed = srcinfo.to_string().parse::<Srcinfo>().unwrap();
+        let pkg = reparsed.pkg("a").unwrap();
+        assert_eq!(pkg.provenance("pkgdesc", None), Provenance::Overridden);
+        assert_eq!(
+            pkg.provenance("depends", Some("x86_64")),
+            Provenance::Overridden
+        );
+    }
+
+    #[test]
+    fn test_fmt_cksums() {
+        let original = include_str!("../tests/srcinfo/cksums");
+        let srcinfo = original.parse::<Srcinfo>().unwrap();
+        let output = srcinfo.to_string();
+
+        assert!(output.contains("\tcksums = 1392746235\n\tcksums_x86_64 = 3920483752\n"));
+        assert!(output.find("cksums").unwrap() < output.find("sha256sums").unwrap());
+        assert_eq!(output.parse::<Srcinfo>().unwrap(), srcinfo);
+    }
 }
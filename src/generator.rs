@@ -0,0 +1,65 @@
+/// The tool that generated a .SRCINFO, read from the "# Generated by ..." header comment.
+#[derive(Clone, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Generator {
+    /// The name of the tool. E.g. "makepkg"
+    pub tool: String,
+    /// The version of the tool. E.g. "6.0.2"
+    pub version: String,
+}
+
+impl Generator {
+    /// Creates a new Generator.
+    pub fn new<S: Into<String>, V: Into<String>>(tool: S, version: V) -> Generator {
+        Generator {
+            tool: tool.into(),
+            version: version.into(),
+        }
+    }
+
+    /// Reads a Generator from a single comment line in the format "Generated by tool version".
+    ///
+    /// The leading '#' is optional. Anything after the version is ignored.
+    ///
+    /// ```
+    /// use srcinfo::Generator;
+    ///
+    /// let generator = Generator::from_comment("# Generated by makepkg 6.0.2").unwrap();
+    /// assert_eq!(generator, Generator::new("makepkg", "6.0.2"));
+    ///
+    /// assert!(Generator::from_comment("# Generated by makepkg").is_none());
+    /// ```
+    pub fn from_comment(comment: &str) -> Option<Generator> {
+        let comment = comment.trim();
+        let comment = comment.strip_prefix('#').unwrap_or(comment).trim_start();
+        let mut words = comment.strip_prefix("Generated by ")?.split_whitespace();
+        let (tool, version) = (words.next()?, words.next()?);
+        Some(Generator::new(tool, version))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_comment() {
+        assert_eq!(
+            Generator::from_comment("Generated by mksrcinfo v8"),
+            Some(Generator::new("mksrcinfo", "v8"))
+        );
+        assert_eq!(
+            Generator::from_comment("#Generated by  makepkg  5.0.1 "),
+            Some(Generator::new("makepkg", "5.0.1"))
+        );
+        assert_eq!(
+            Generator::from_comment("# Generated by makepkg 6.0.2 extra"),
+            Some(Generator::new("makepkg", "6.0.2"))
+        );
+        assert_eq!(Generator::from_comment("Generated by"), None);
+        assert_eq!(
+            Generator::from_comment("Wed Oct 19 20:57:35 UTC 2016"),
+            None
+        );
+    }
+}
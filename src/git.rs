@@ -0,0 +1,142 @@
+use std::io;
+use std::path::Path;
+
+use git2::{ErrorCode, Repository};
+
+use crate::error::Error;
+use crate::options::ParseOptions;
+use crate::srcinfo::Srcinfo;
+
+// git errors are reported as IoErrors so that ErrorKind stays the same with or without the
+// git feature
+fn git_error(err: git2::Error) -> Error {
+    let kind = match err.code() {
+        ErrorCode::NotFound => io::ErrorKind::NotFound,
+        _ => io::ErrorKind::Other,
+    };
+    io::Error::new(kind, err).into()
+}
+
+impl ParseOptions {
+    /// Parse the .SRCINFO of a git repository at a given revision.
+    ///
+    /// Requires the `git` feature.
+    pub fn parse_git<P: AsRef<Path>>(&self, repo: P, rev: &str) -> Result<Srcinfo, Error> {
+        let repo = Repository::open(repo).map_err(git_error)?;
+        let tree = repo
+            .revparse_single(rev)
+            .and_then(|obj| obj.peel_to_tree())
+            .map_err(git_error)?;
+        let blob = tree
+            .get_path(Path::new(".SRCINFO"))
+            .and_then(|entry| entry.to_object(&repo))
+            .and_then(|obj| obj.peel_to_blob())
+            .map_err(git_error)?;
+
+        self.parse_bytes(blob.content())
+    }
+}
+
+impl Srcinfo {
+    /// Parse the .SRCINFO of a git repository at a given revision.
+    ///
+    /// The file is read straight from the object database so the repository may be bare and
+    /// the working tree is never touched. `rev` can be anything git understands, such as a
+    /// commit hash, branch, tag or `HEAD~2`.
+    ///
+    /// Git errors, including a missing revision or .SRCINFO, are returned as an
+    /// [`ErrorKind::IoError`](crate::ErrorKind::IoError). Missing objects use
+    /// [`io::ErrorKind::NotFound`].
+    ///
+    /// Requires the `git` feature.
+    ///
+    /// ```no_run
+    /// # use srcinfo::Error;
+    /// use srcinfo::Srcinfo;
+    ///
+    /// # fn test() -> Result<(), Error> {
+    /// let srcinfo = Srcinfo::from_git("aur/example", "HEAD~1")?;
+    /// println!("{}", srcinfo.version());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn from_git<P: AsRef<Path>>(repo: P, rev: &str) -> Result<Srcinfo, Error> {
+        ParseOptions::default().parse_git(repo, rev)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::ErrorKind;
+    use crate::test_util::{LIBCPP, TempPath};
+    use git2::{Oid, Signature};
+
+    fn commit(repo: &Repository, srcinfo: &str) -> Oid {
+        let blob = repo.blob(srcinfo.as_bytes()).unwrap();
+        let mut tree = repo.treebuilder(None).unwrap();
+        tree.insert(".SRCINFO", blob, 0o100644).unwrap();
+        let tree = repo.find_tree(tree.write().unwrap()).unwrap();
+
+        let sig = Signature::now("test", "test@example.com").unwrap();
+        let parent = repo.head().ok().and_then(|h| h.peel_to_commit().ok());
+        let parents = parent.iter().collect::<Vec<_>>();
+        repo.commit(Some("HEAD"), &sig, &sig, "update", &tree, &parents)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_from_git() {
+        let input = LIBCPP;
+        let dir = TempPath::new("git");
+        let repo = Repository::init(&dir).unwrap();
+
+        let first = commit(&repo, input);
+        commit(&repo, &input.replace("pkgrel = 1", "pkgrel = 2"));
+
+        let srcinfo = Srcinfo::from_git(&dir, "HEAD").unwrap();
+        assert_eq!(srcinfo.pkgrel(), "2");
+        let srcinfo = Srcinfo::from_git(&dir, "HEAD~1").unwrap();
+        assert_eq!(srcinfo.pkgrel(), "1");
+        let srcinfo = Srcinfo::from_git(&dir, &first.to_string()).unwrap();
+        assert_eq!(srcinfo, input.parse().unwrap());
+
+        let err = Srcinfo::from_git(&dir, "HEAD~2").unwrap_err();
+
+        match err.kind {
+            ErrorKind::IoError(ref e) => assert_eq!(e.kind(), io::ErrorKind::NotFound),
+            _ => panic!("{:?}", err),
+        }
+    }
+
+    #[test]
+    fn test_from_git_bare() {
+        let dir = TempPath::new("git-bare");
+        let repo = Repository::init_bare(&dir).unwrap();
+        commit(&repo, LIBCPP);
+        let srcinfo = Srcinfo::from_git(&dir, "HEAD").unwrap();
+        let err = Srcinfo::from_git(&dir, "HEAD:.SRCINFO").unwrap_err();
+
+        assert_eq!(srcinfo.pkgbase(), "libc++");
+        assert!(matches!(err.kind, ErrorKind::IoError(_)));
+    }
+
+    #[test]
+    fn test_from_git_errors() {
+        let dir = TempPath::new("git-errors");
+        let repo = Repository::init(&dir).unwrap();
+        commit(&repo, "pkgbase = foo");
+
+        let missing = Srcinfo::from_git(dir.join("missing"), "HEAD").unwrap_err();
+        let invalid = Srcinfo::from_git(&dir, "HEAD").unwrap_err();
+
+        match missing.kind {
+            ErrorKind::IoError(ref e) => assert_eq!(e.kind(), io::ErrorKind::NotFound),
+            _ => panic!("{:?}", missing),
+        }
+        match invalid.kind {
+            ErrorKind::MissingField(ref field) => assert_eq!(field, "pkgname"),
+            _ => panic!("{:?}", invalid),
+        }
+    }
+}
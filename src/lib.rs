@@ -5,9 +5,10 @@
 //! Srcinfo focuses on correctness of parsing, especially
 //! with split packages and architecture specific fields.
 //!
-//! Srcinfo only aims to parse. This crate does not attempt to
-//! perform any version comparison, dependency checking or any other
-//! extra functionality.
+//! Srcinfo mainly aims to parse. Beyond that it only provides
+//! version comparison matching pacman's vercmp with [`Version`] and
+//! dependency parsing with [`Dependency`]. This crate does not attempt to
+//! resolve dependencies or perform any other extra functionality.
 //!
 //! ## Quickstart
 //!
@@ -15,7 +16,7 @@
 //!
 //! ```
 //! # use srcinfo::Error;
-//! use srcinfo::{Srcinfo, ArchVec};
+//! use srcinfo::{Srcinfo, SyntaxTree, ArchVec};
 //!
 //! # fn test() -> Result<(), Error> {
 //! // Create a srcinfo from a string
@@ -78,17 +79,71 @@
 //! // the new sring will semanticly match the original .SRCINFO
 //! // but field order and whitespace will change, comments will be removed
 //! let srcinfo = srcinfo.to_string();
+//!
+//! // To edit a .SRCINFO while keeping its formatting use a SyntaxTree
+//! let mut tree = SyntaxTree::from(srcinfo.as_str());
+//! tree.field_mut(None, "pkgrel").unwrap().set_value("2");
+//! let srcinfo = tree.to_srcinfo()?;
+//! assert_eq!(srcinfo.pkgrel(), "2");
 //! # Ok(())
 //! # }
 //! ```
+//!
+//! ## Features
+//!
+//! - `serde`: implement Serialize and Deserialize for the parsed types
+//! - `tokio`: parse from an `AsyncBufRead` with `Srcinfo::from_async_buf` and
+//!   `Srcinfo::from_path_async`
+//! - `rayon`: find and parse every .SRCINFO under a directory in parallel with
+//!   `Srcinfo::from_dir`
+//! - `git`: read the .SRCINFO of a local git repository at any revision with
+//!   `Srcinfo::from_git`
+//! - `tarball`: read the .SRCINFO out of an AUR snapshot `.tar.gz` with
+//!   `Srcinfo::from_tarball`
 
 #![warn(missing_docs)]
 mod archvec;
+#[cfg(feature = "tokio")]
+mod asynchronous;
+mod builder;
+mod depend;
+mod edit;
+#[cfg(feature = "rayon")]
+mod dir;
 mod error;
+mod event;
 mod fmt;
+mod generator;
+#[cfg(feature = "git")]
+mod git;
+mod options;
 mod parse;
+mod provenance;
+mod resolve;
+mod span;
 mod srcinfo;
+mod srcinfo_ref;
+mod syntax;
+#[cfg(feature = "tarball")]
+mod tarball;
+#[cfg(all(test, any(feature = "rayon", feature = "git", feature = "tarball")))]
+mod test_util;
+mod validate;
+mod version;
+mod view;
 
 pub use crate::archvec::*;
+pub use crate::builder::*;
+pub use crate::depend::*;
 pub use crate::error::*;
+pub use crate::event::*;
+pub use crate::generator::*;
+pub use crate::options::*;
+pub use crate::provenance::*;
+pub use crate::resolve::*;
+pub use crate::span::*;
 pub use crate::srcinfo::*;
+pub use crate::srcinfo_ref::*;
+pub use crate::syntax::*;
+pub use crate::version::*;
+pub use crate::view::*;
@@ -0,0 +1,212 @@
+use std::borrow::Cow;
+use std::fs;
+use std::io::BufRead;
+use std::path::Path;
+
+use crate::error::{Error, ErrorKind};
+use crate::parse::Parser;
+use crate::span::Spans;
+use crate::srcinfo::Srcinfo;
+use crate::srcinfo_ref::SrcinfoRef;
+use crate::syntax::SyntaxTree;
+
+/// Options that change how a .SRCINFO is parsed.
+///
+/// The default options match the behaviour of [`Srcinfo::from_str`](std::str::FromStr),
+/// [`Srcinfo::from_buf`] and [`Srcinfo::from_path`].
+///
+/// ```
+/// use srcinfo::{ErrorKind, ParseOptions};
+///
+/// let mut options = ParseOptions::new();
+/// options.strict = true;
+///
+/// let err = options.parse_str("
+/// pkgbase = example
+/// pkgver = 1.5.0
+/// pkgrel = 5
+/// pkgver = 1.6.0
+///
+/// pkgname = example").unwrap_err();
+///
+/// assert!(matches!(err.kind, ErrorKind::DuplicateField(_)));
+/// ```
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[non_exhaustive]
+pub struct ParseOptions {
+    /// Reject input that makepkg would never generate.
+    ///
+    /// Unknown keys fail with [`ErrorKind::UnknownKey`](crate::ErrorKind::UnknownKey) instead of
+    /// being stored in `extra`. Setting a single value field twice in the same section fails
+    /// with [`ErrorKind::DuplicateField`](crate::ErrorKind::DuplicateField) instead of
+    /// overwriting the first value. Declaring a pkgname twice fails with
+    /// [`ErrorKind::DuplicatePkgname`](crate::ErrorKind::DuplicatePkgname).
+    pub strict: bool,
+    /// Recover from errors that commonly appear in hand edited files.
+    ///
+    /// Each recovery is reported as a warning by the `_with_warnings` methods, such as
+    /// [`ParseOptions::parse_str_with_warnings`]. The warning holds the error that would
+    /// otherwise have been returned:
+    ///
+    /// - [`ErrorKind::UndeclaredArch`]: the value is dropped as the architecture was not
+    ///   declared in `arch`.
+    /// - [`ErrorKind::KeyAfterPkgname`]: the value is moved back to the pkgbase section.
+    /// - [`ErrorKind::EmptyValue`]: an empty value in the pkgbase section is skipped.
+    ///
+    /// All other errors still fail.
+    pub lenient: bool,
+    /// Replace invalid UTF-8 with U+FFFD instead of failing.
+    ///
+    /// Without this, byte input that is not valid UTF-8 fails with
+    /// [`ErrorKind::InvalidUtf8`](crate::ErrorKind::InvalidUtf8).
+    pub lossy_utf8: bool,
+}
+
+// decodes the input, on failure reporting the line and byte offset of the first invalid byte
+pub(crate) fn decode(b: &[u8], lossy: bool) -> Result<Cow<'_, str>, Error> {
+    if lossy {
+        return Ok(String::from_utf8_lossy(b));
+    }
+
+    match std::str::from_utf8(b) {
+        Ok(s) => Ok(Cow::Borrowed(s)),
+        Err(e) => {
+            let offset = e.valid_up_to();
+            let start = b[..offset]
+                .iter()
+                .rposition(|&c| c == b'\n')
+                .map_or(0, |i| i + 1);
+            let end = b[offset..]
+                .iter()
+                .position(|&c| c == b'\n')
+                .map_or(b.len(), |i| offset + i);
+            let number = b[..start].iter().filter(|&&c| c == b'\n').count() + 1;
+            let line = String::from_utf8_lossy(&b[start..end]);
+            Err(Error::new(
+                ErrorKind::InvalidUtf8(offset),
+                line.trim(),
+                number,
+            ))
+        }
+    }
+}
+
+pub(crate) fn read_buf<T: BufRead>(mut b: T, lossy: bool) -> Result<String, Error> {
+    let mut bytes = Vec::new();
+    b.read_to_end(&mut bytes)?;
+    decode_owned(bytes, lossy)
+}
+
+pub(crate) fn decode_owned(bytes: Vec<u8>, lossy: bool) -> Result<String, Error> {
+    match decode(&bytes, lossy)? {
+        // the input was valid so this can not fail
+        Cow::Borrowed(_) => Ok(String::from_utf8(bytes).unwrap()),
+        Cow::Owned(s) => Ok(s),
+    }
+}
+
+impl ParseOptions {
+    /// Creates the default ParseOptions
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Parse a string.
+    pub fn parse_str(&self, s: &str) -> Result<Srcinfo, Error> {
+        self.parse_ref(s).map(|srcinfo| srcinfo.to_owned())
+    }
+
+    /// Parse a string into a [`SrcinfoRef`] that borrows from the string.
+    pub fn parse_ref<'a>(&self, s: &'a str) -> Result<SrcinfoRef<'a>, Error> {
+        Parser::parse(s, *self)
+    }
+
+    /// Parse a string, also returning the warnings for every error that was recovered from.
+    ///
+    /// The warnings are only ever non empty when [`ParseOptions::lenient`] is set.
+    ///
+    /// ```
+    /// use srcinfo::{ErrorKind, ParseOptions};
+    ///
+    /// let mut options = ParseOptions::new();
+    /// options.lenient = true;
+    ///
+    /// let (srcinfo, warnings) = options.parse_str_with_warnings("
+    /// pkgbase = example
+    /// pkgver = 1.5.0
+    /// pkgrel = 5
+    /// arch = x86_64
+    ///
+    /// pkgname = example
+    /// depends_i686 = foo").unwrap();
+    ///
+    /// assert_eq!(srcinfo.pkgs()[0].depends().arch("i686").count(), 0);
+    /// assert!(matches!(warnings[0].kind, ErrorKind::UndeclaredArch(_, _)));
+    /// assert_eq!(warnings[0].line.as_ref().unwrap().number, 8);
+    /// ```
+    pub fn parse_str_with_warnings(&self, s: &str) -> Result<(Srcinfo, Vec<Error>), Error> {
+        self.parse_ref_with_warnings(s)
+            .map(|(srcinfo, warnings)| (srcinfo.to_owned(), warnings))
+    }
+
+    /// Parse a string into a [`SrcinfoRef`], also returning the warnings for every error
+    /// that was recovered from.
+    pub fn parse_ref_with_warnings<'a>(
+        &self,
+        s: &'a str,
+    ) -> Result<(SrcinfoRef<'a>, Vec<Error>), Error> {
+        Parser::parse_with_warnings(s, *self)
+    }
+
+    /// Parse a BufRead, also returning the warnings for every error that was recovered from.
+    pub fn parse_buf_with_warnings<T: BufRead>(
+        &self,
+        b: T,
+    ) -> Result<(Srcinfo, Vec<Error>), Error> {
+        self.parse_str_with_warnings(&read_buf(b, self.lossy_utf8)?)
+    }
+
+    /// Parse a [`SyntaxTree`].
+    pub fn parse_tree(&self, tree: &SyntaxTree) -> Result<Srcinfo, Error> {
+        Parser::parse_tree(tree, *self).map(|srcinfo| srcinfo.to_owned())
+    }
+
+    /// Parse a BufRead, collecting every error instead of stopping at the first one.
+    ///
+    /// See [`Srcinfo::parse_with_diagnostics`]. Errors that [`ParseOptions::lenient`]
+    /// recovers from are not included.
+    pub fn parse_with_diagnostics<T: BufRead>(&self, b: T) -> (Srcinfo, Vec<Error>) {
+        let s = match read_buf(b, self.lossy_utf8) {
+            Ok(s) => s,
+            Err(e) => return (Srcinfo::default(), vec![e]),
+        };
+
+        let (srcinfo, errors) = Parser::parse_with_diagnostics(&s, *self);
+        (srcinfo.to_owned(), errors)
+    }
+
+    /// Parse a BufRead, also recording the location of every value.
+    ///
+    /// See [`Srcinfo::parse_with_spans`].
+    pub fn parse_with_spans<T: BufRead>(&self, b: T) -> Result<(Srcinfo, Spans), Error> {
+        let s = read_buf(b, self.lossy_utf8)?;
+
+        let (srcinfo, spans) = Parser::parse_with_spans(&s, *self)?;
+        Ok((srcinfo.to_owned(), spans))
+    }
+
+    /// Parse bytes that may not be valid UTF-8.
+    pub fn parse_bytes(&self, b: &[u8]) -> Result<Srcinfo, Error> {
+        self.parse_str(&decode(b, self.lossy_utf8)?)
+    }
+
+    /// Parse a BufRead.
+    pub fn parse_buf<T: BufRead>(&self, b: T) -> Result<Srcinfo, Error> {
+        self.parse_str(&read_buf(b, self.lossy_utf8)?)
+    }
+
+    /// Parse the file at a given path.
+    pub fn parse_path<P: AsRef<Path>>(&self, s: P) -> Result<Srcinfo, Error> {
+        self.parse_bytes(&fs::read(s)?)
+    }
+}
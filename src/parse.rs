@@ -1,183 +1,298 @@
-use std::io::BufRead;
+use std::borrow::Cow;
 
-use crate::ArchVec;
-use crate::archvec::ArchVecs;
+use crate::archvec::{ArchVecRef, ArchVecsRef};
 use crate::error::{Error, ErrorKind};
-use crate::srcinfo::{Package, Srcinfo};
+use crate::event::Event;
+use crate::options::ParseOptions;
+use crate::provenance::{Provenance, inherit_fields};
+use crate::span::{SectionSpans, Span, Spans};
+use crate::srcinfo_ref::{PackageRef, SrcinfoRef};
+use crate::syntax::SyntaxTree;
+
+// joins ("depends", Some("x86_64")) back into "depends_x86_64" for error messages
+pub(crate) fn key_arch(key: &str, arch: Option<&str>) -> String {
+    match arch {
+        Some(arch) => format!("{}_{}", key, arch),
+        None => key.to_string(),
+    }
+}
 
-macro_rules! merge {
-    ($slf:ident, $base:ident, $field:ident) => {
-        if $base.$field.is_none() && !has_override(&$slf.empty_overrides, stringify!($field), None)
-        {
-            $base.$field = $slf.srcinfo.pkg.$field.clone();
-        }
-    };
+fn append_arch_strings<'a>(vecs: &mut ArchVecsRef<'a>, arch: Option<&'a str>, value: &'a str) {
+    if let Some(vec) = vecs.vecs.iter_mut().find(|v| v.arch() == arch) {
+        vec.values.push(value);
+    } else {
+        vecs.vecs.push(ArchVecRef {
+            arch,
+            values: vec![value],
+        });
+    }
 }
 
-macro_rules! merge_vec {
-    ($slf:ident, $base:ident, $field:ident) => {
-        if $base.$field.is_empty() && !has_override(&$slf.empty_overrides, stringify!($field), None)
-        {
-            $base.$field = $slf.srcinfo.pkg.$field.clone();
-        }
-    };
+#[derive(Default)]
+pub struct Parser<'a> {
+    srcinfo: SrcinfoRef<'a>,
+    has_pkg: bool,
+    line: usize,
+    offset: usize,
+    spans: Option<Spans>,
+    value_span: Option<Span>,
+    options: ParseOptions,
+    // recovered errors from the current line
+    recovered: Vec<ErrorKind>,
+    warnings: Vec<Error>,
 }
 
-macro_rules! merge_arch_string {
-    ($slf:ident, $base:ident, $field:ident) => {
-        for arch_string in &$slf.srcinfo.pkg.$field {
-            if $base.$field.get(arch_string.arch()).is_none()
-                && !has_override(
-                    &$slf.empty_overrides,
-                    stringify!($field),
-                    arch_string.arch(),
-                )
-            {
-                $base.$field.vecs.push(arch_string.clone());
-            }
+impl<'a> Parser<'a> {
+    pub fn parse(s: &'a str, options: ParseOptions) -> Result<SrcinfoRef<'a>, Error> {
+        Parser::parse_with_warnings(s, options).map(|(srcinfo, _)| srcinfo)
+    }
+
+    pub fn parse_with_warnings(
+        s: &'a str,
+        options: ParseOptions,
+    ) -> Result<(SrcinfoRef<'a>, Vec<Error>), Error> {
+        let mut parser = Parser {
+            options,
+            ..Default::default()
+        };
+
+        for line in s.split_inclusive('\n') {
+            parser.next_line(line, line.len())?;
         }
-    };
-}
 
-// Splits a "key = pair"
-fn split_pair(s: &str) -> Result<(&str, Option<&str>), ErrorKind> {
-    let split = s.split_once('=');
-    let (key, value) = split.ok_or_else(|| ErrorKind::EmptyValue(s.to_string()))?;
-    let (key, value) = (key.trim(), value.trim());
-    if key.is_empty() {
-        return Err(ErrorKind::EmptyKey);
+        let warnings = std::mem::take(&mut parser.warnings);
+        Ok((parser.finish()?, warnings))
     }
-    Ok((key, empty_to_none(value)))
-}
 
-// splits depends_foo to ("depends", Some("foo"))
-fn split_key_arch(s: &str) -> (&str, Option<&str>) {
-    let mut split = s.splitn(2, '_');
-    let key = split.next().unwrap();
-    let arch = split.next();
-    (key, arch)
-}
+    pub fn parse_tree(
+        tree: &'a SyntaxTree,
+        options: ParseOptions,
+    ) -> Result<SrcinfoRef<'a>, Error> {
+        let mut parser = Parser {
+            options,
+            ..Default::default()
+        };
 
-fn empty_to_none(s: &str) -> Option<&str> {
-    (!s.is_empty()).then_some(s)
-}
+        for line in tree.lines() {
+            let len = line.text().len() + line.ending().as_str().len();
+            parser.next_line(line.text(), len)?;
+        }
 
-fn append_arch_strings(vecs: &mut ArchVecs, arch: Option<&str>, value: &str) {
-    if let Some(vec) = vecs.vecs.iter_mut().find(|v| v.arch() == arch) {
-        vec.values.push(value.to_string());
-    } else {
-        vecs.vecs.push(ArchVec {
-            arch: arch.map(str::to_string),
-            values: vec![value.to_string()],
-        });
+        parser.finish()
     }
-}
 
-fn has_override(overrides: &[(String, Option<String>)], key: &str, arch: Option<&str>) -> bool {
-    overrides
-        .iter()
-        .map(|(k, a)| (k.as_str(), a.as_deref()))
-        .any(|x| x == (key, arch))
-}
+    pub fn parse_with_spans(
+        s: &'a str,
+        options: ParseOptions,
+    ) -> Result<(SrcinfoRef<'a>, Spans), Error> {
+        let mut parser = Parser {
+            spans: Some(Spans::default()),
+            options,
+            ..Default::default()
+        };
 
-#[derive(Default)]
-pub struct Parser {
-    srcinfo: Srcinfo,
-    empty_overrides: Vec<(String, Option<String>)>,
-    has_pkg: bool,
-}
+        for line in s.split_inclusive('\n') {
+            parser.next_line(line, line.len())?;
+        }
 
-impl Parser {
-    pub fn parse<T: BufRead>(s: T) -> Result<Srcinfo, Error> {
-        let mut parser = Parser::default();
+        let spans = parser.spans.take().unwrap_or_default();
+        Ok((parser.finish()?, spans))
+    }
 
-        for (n, line) in s.lines().enumerate() {
-            let line = line?;
+    pub fn parse_with_diagnostics(
+        s: &'a str,
+        options: ParseOptions,
+    ) -> (SrcinfoRef<'a>, Vec<Error>) {
+        let mut parser = Parser {
+            options,
+            ..Default::default()
+        };
+        let mut errors = Vec::new();
 
-            parser
-                .parse_line(&line)
-                .map_err(|e| Error::new(e, line.trim(), n + 1))?;
+        for line in s.split_inclusive('\n') {
+            if let Err(e) = parser.next_line(line, line.len()) {
+                errors.push(e);
+            }
         }
 
         parser.merge_current_package();
-        parser.check_missing()?;
+        errors.extend(parser.missing_fields().into_iter().map(Error::from));
 
-        Ok(parser.srcinfo)
+        (parser.srcinfo, errors)
     }
 
-    fn parse_line(&mut self, line: &str) -> Result<(), ErrorKind> {
-        let line = line.trim();
+    // parses a line, len is the length of the line including the line terminator
+    fn next_line(&mut self, line: &'a str, len: usize) -> Result<(), Error> {
+        self.line += 1;
+        let res = self
+            .parse_line(line)
+            .map_err(|e| Error::new(e, line.trim(), self.line));
+        for kind in self.recovered.drain(..) {
+            self.warnings.push(Error::new(kind, line.trim(), self.line));
+        }
+        self.offset += len;
+        res
+    }
 
-        if self.srcinfo.pkgbase().is_empty() && line.trim_start().starts_with('#') {
-            let comment = line[1..].trim();
-            if !self.srcinfo.comment.is_empty() {
-                self.srcinfo.comment.push('\n');
+    fn finish(mut self) -> Result<SrcinfoRef<'a>, Error> {
+        self.merge_current_package();
+        self.check_missing()?;
+        Ok(self.srcinfo)
+    }
+
+    fn parse_line(&mut self, line: &'a str) -> Result<(), ErrorKind> {
+        let Some(event) = Event::from_line(line)? else {
+            return Ok(());
+        };
+
+        if self.spans.is_some() {
+            self.value_span = match event {
+                Event::Pkgbase(value) | Event::Pkgname(value) | Event::Field { value, .. } => {
+                    Some(self.span(line, value))
+                }
+                Event::Comment(_) | Event::EmptyOverride { .. } => None,
+            };
+        }
+
+        self.next_event(event)
+    }
+
+    fn next_event(&mut self, event: Event<'a>) -> Result<(), ErrorKind> {
+        match event {
+            Event::Comment(comment) => {
+                if self.srcinfo.pkgbase().is_empty() {
+                    if self.srcinfo.comment.is_empty() {
+                        self.srcinfo.comment = Cow::Borrowed(comment);
+                    } else {
+                        let header = self.srcinfo.comment.to_mut();
+                        header.push('\n');
+                        header.push_str(comment);
+                    }
+                }
+                Ok(())
+            }
+            Event::Pkgbase(pkgbase) => self.set_pkgbase(pkgbase),
+            Event::Pkgname(pkgname) => {
+                self.check_key_before_pkgbase("pkgname")?;
+                if self.options.strict && self.srcinfo.pkgnames().any(|p| p == pkgname) {
+                    return Err(ErrorKind::DuplicatePkgname(pkgname.to_string()));
+                }
+                self.push_pkg(pkgname);
+                Ok(())
+            }
+            Event::Field { key, arch, value } => {
+                self.check_key_before_pkgbase(&key_arch(key, arch))?;
+                self.set_field(key, arch, value)
+            }
+            Event::EmptyOverride {
+                key: "pkgbase",
+                arch: None,
+            } => {
+                self.check_duplicate_pkgbase()?;
+                Err(ErrorKind::EmptyValue("pkgbase".to_string()))
+            }
+            Event::EmptyOverride {
+                key: "pkgname",
+                arch: None,
+            } => {
+                self.check_key_before_pkgbase("pkgname")?;
+                Err(ErrorKind::EmptyValue("pkgname".to_string()))
+            }
+            Event::EmptyOverride { key, arch } => {
+                self.check_key_before_pkgbase(&key_arch(key, arch))?;
+                if self.has_pkg {
+                    self.last_pkg()
+                        .add_provenance(key, arch, Provenance::Cleared);
+                    Ok(())
+                } else {
+                    self.recover(Err(ErrorKind::EmptyValue(key.to_string())))
+                }
             }
-            self.srcinfo.comment.push_str(comment);
         }
+    }
 
-        if line.is_empty() || line.starts_with('#') {
-            return Ok(());
+    // value must be a slice of line
+    fn span(&self, line: &str, value: &str) -> Span {
+        let start = value.as_ptr() as usize - line.as_ptr() as usize;
+        Span {
+            line: self.line,
+            column: line[..start].chars().count() + 1,
+            offset: self.offset + start,
+            len: value.len(),
         }
+    }
 
-        let (key, pair) = split_pair(line)?;
-        self.set_header_or_field(key, pair)
+    // records the span in the section the value was stored in, which is the pkgbase for
+    // pkgbase keys even when lenient mode accepted them after a pkgname
+    fn push_span(&mut self, key: &str, arch: Option<&str>, in_base: bool) {
+        if let (Some(spans), Some(span)) = (&mut self.spans, self.value_span) {
+            let section = match spans.pkgs.last_mut() {
+                Some(pkg) if !in_base => pkg,
+                _ => &mut spans.base,
+            };
+            section.push(key, arch, span);
+        }
     }
 
-    fn add_override(&mut self, key: &str, arch: Option<&str>) {
-        if !has_override(&self.empty_overrides, key, arch) {
-            self.empty_overrides
-                .push((key.to_string(), arch.map(|s| s.to_string())));
+    // in lenient mode turns the error into a warning
+    fn recover(&mut self, res: Result<(), ErrorKind>) -> Result<(), ErrorKind> {
+        match res {
+            Err(e) if self.options.lenient => {
+                self.recovered.push(e);
+                Ok(())
+            }
+            res => res,
         }
     }
 
-    fn last_pkg(&mut self) -> &mut Package {
+    // whether the current package section cleared the field
+    fn has_override(&self, key: &str, arch: Option<&str>) -> bool {
+        self.has_pkg
+            && self
+                .srcinfo
+                .pkgs
+                .last()
+                .is_some_and(|p| p.provenance(key, arch) == Provenance::Cleared)
+    }
+
+    fn last_pkg(&mut self) -> &mut PackageRef<'a> {
         let pkg = &mut self.srcinfo.pkg;
         self.srcinfo.pkgs.last_mut().unwrap_or(pkg)
     }
 
-    #[allow(clippy::cognitive_complexity)]
     fn merge_current_package(&mut self) {
         if let Some(package) = self.srcinfo.pkgs.last_mut() {
-            merge!(self, package, pkgdesc);
-            merge_vec!(self, package, arch);
-            merge!(self, package, url);
-            merge_vec!(self, package, license);
-            merge_vec!(self, package, groups);
-            merge_arch_string!(self, package, depends);
-            merge_arch_string!(self, package, optdepends);
-            merge_arch_string!(self, package, provides);
-            merge_arch_string!(self, package, conflicts);
-            merge_arch_string!(self, package, replaces);
-            merge_vec!(self, package, backup);
-            merge_vec!(self, package, options);
-            merge!(self, package, install);
-            merge!(self, package, changelog);
-            self.empty_overrides.clear();
+            inherit_fields!(package, &self.srcinfo.pkg);
         }
     }
 
+    fn missing_fields(&self) -> Vec<ErrorKind> {
+        [
+            ("pkgbase", self.srcinfo.pkgbase().is_empty()),
+            ("pkgname", self.srcinfo.pkgs.is_empty()),
+            ("pkgver", self.srcinfo.pkgver().is_empty()),
+            ("pkgrel", self.srcinfo.pkgrel().is_empty()),
+        ]
+        .into_iter()
+        .filter(|&(_, missing)| missing)
+        .map(|(field, _)| ErrorKind::MissingField(field.to_string()))
+        .collect()
+    }
+
     fn check_missing(&self) -> Result<(), ErrorKind> {
-        Err(ErrorKind::MissingField(
-            if self.srcinfo.pkgbase().is_empty() {
-                "pkgbase"
-            } else if self.srcinfo.pkgs.is_empty() {
-                "pkgname"
-            } else if self.srcinfo.pkgver().is_empty() {
-                "pkgver"
-            } else if self.srcinfo.pkgrel().is_empty() {
-                "pkgrel"
-            } else {
-                return Ok(());
-            }
-            .to_string(),
-        ))
+        match self.missing_fields().into_iter().next() {
+            Some(err) => Err(err),
+            None => Ok(()),
+        }
     }
 
     // check that the _arch prefix of a field actually exists and is not any
-    fn check_arch(&self, arches: &[String], key: &str, arch: &str) -> Result<(), ErrorKind> {
-        if arch == "any" || !arches.iter().any(|a| a.as_str() == arch) {
-            Err(ErrorKind::UndeclaredArch(key.to_string(), arch.to_string()))
+    fn check_arch(&self, arches: &[&str], key: &str, arch: &str) -> Result<(), ErrorKind> {
+        if arch == "any" || !arches.contains(&arch) {
+            Err(ErrorKind::UndeclaredArch(
+                key_arch(key, Some(arch)),
+                arch.to_string(),
+            ))
         } else {
             Ok(())
         }
@@ -186,21 +301,43 @@ impl Parser {
     fn check_not_arch_specific(&self, key: &str, arch: Option<&str>) -> Result<(), ErrorKind> {
         match arch {
             None => Ok(()),
-            Some(_) => Err(ErrorKind::NotArchSpecific(key.to_string())),
+            Some(_) => Err(ErrorKind::NotArchSpecific(key_arch(key, arch))),
         }
     }
 
-    fn check_key_after_pkgname(&self, key: &str) -> Result<(), ErrorKind> {
+    // check that a single value field has not already been set in the current section
+    fn check_duplicate(&self, key: &str) -> Result<(), ErrorKind> {
+        let base = &self.srcinfo.base;
+        let pkg = self.srcinfo.pkgs.last().unwrap_or(&self.srcinfo.pkg);
+
+        let set = match key {
+            "pkgver" => !self.has_pkg && !base.pkgver.is_empty(),
+            "pkgrel" => !self.has_pkg && !base.pkgrel.is_empty(),
+            "epoch" => !self.has_pkg && base.epoch.is_some(),
+            "pkgdesc" => pkg.pkgdesc.is_some(),
+            "url" => pkg.url.is_some(),
+            "install" => pkg.install.is_some(),
+            "changelog" => pkg.changelog.is_some(),
+            _ => false,
+        };
+
+        if set {
+            Err(ErrorKind::DuplicateField(key.to_string()))
+        } else {
+            Ok(())
+        }
+    }
+
+    fn check_key_after_pkgname(&self, key: &str, arch: Option<&str>) -> Result<(), ErrorKind> {
         if self.has_pkg {
-            Err(ErrorKind::KeyAfterPkgname(key.to_string()))
+            Err(ErrorKind::KeyAfterPkgname(key_arch(key, arch)))
         } else {
             Ok(())
         }
     }
 
-    fn push_pkg(&mut self, pkgname: &str) {
-        let pkgname = pkgname.to_string();
-        let pkg = Package {
+    fn push_pkg(&mut self, pkgname: &'a str) {
+        let pkg = PackageRef {
             pkgname,
             ..Default::default()
         };
@@ -208,93 +345,130 @@ impl Parser {
         self.merge_current_package();
         self.has_pkg = true;
         self.srcinfo.pkgs.push(pkg);
+
+        if let (Some(spans), Some(header)) = (&mut self.spans, self.value_span) {
+            spans.pkgs.push(SectionSpans {
+                header,
+                ..Default::default()
+            });
+        }
+    }
+
+    fn check_duplicate_pkgbase(&self) -> Result<(), ErrorKind> {
+        if self.srcinfo.pkgbase().is_empty() {
+            Ok(())
+        } else {
+            Err(ErrorKind::DuplicatePkgbase)
+        }
     }
 
-    fn set_pkgbase(&mut self, value: Option<&str>) -> Result<(), ErrorKind> {
-        if !self.srcinfo.pkgbase().is_empty() {
-            return Err(ErrorKind::DuplicatePkgbase);
+    fn set_pkgbase(&mut self, pkgbase: &'a str) -> Result<(), ErrorKind> {
+        self.check_duplicate_pkgbase()?;
+        self.srcinfo.base.pkgbase = pkgbase;
+
+        if let (Some(spans), Some(header)) = (&mut self.spans, self.value_span) {
+            spans.base.header = header;
         }
-        self.srcinfo.base.pkgbase = value
-            .ok_or_else(|| ErrorKind::EmptyValue("pkgbase".to_string()))?
-            .to_string();
 
         Ok(())
     }
 
-    fn set_header_or_field(&mut self, key: &str, value: Option<&str>) -> Result<(), ErrorKind> {
-        if key == "pkgbase" {
-            self.set_pkgbase(value)
-        } else if self.srcinfo.pkgbase().is_empty() {
+    fn check_key_before_pkgbase(&self, key: &str) -> Result<(), ErrorKind> {
+        if self.srcinfo.pkgbase().is_empty() {
             Err(ErrorKind::KeyBeforePkgbase(key.to_string()))
-        } else if key == "pkgname" {
-            let pkgname = value.ok_or_else(|| ErrorKind::EmptyValue(key.to_string()))?;
-            self.push_pkg(pkgname);
-            Ok(())
         } else {
-            self.set_field(key, value)
+            Ok(())
         }
     }
 
-    fn set_field(&mut self, key_arch: &str, value: Option<&str>) -> Result<(), ErrorKind> {
-        let (key, arch) = split_key_arch(key_arch);
-
-        let Some(value) = value else {
-            if self.has_pkg {
-                self.add_override(key, arch);
-                return Ok(());
-            } else {
-                return Err(ErrorKind::EmptyValue(key.to_string()));
-            }
-        };
-
-        if has_override(&self.empty_overrides, key, arch) {
+    fn set_field(
+        &mut self,
+        key: &'a str,
+        arch: Option<&'a str>,
+        value: &'a str,
+    ) -> Result<(), ErrorKind> {
+        if self.has_override(key, arch) {
             return Ok(());
         }
 
         if let Some(arch) = arch {
             let base = &self.srcinfo.pkg;
             let pkg = self.srcinfo.pkgs.last().unwrap_or(base);
-            let pkg_arch =
-                if pkg.arch.is_empty() && !has_override(&self.empty_overrides, "arch", None) {
-                    &base.arch
-                } else {
-                    &pkg.arch
-                };
-            self.check_arch(pkg_arch, key_arch, arch)?;
+            let pkg_arch = if pkg.arch.is_empty() && !self.has_override("arch", None) {
+                &base.arch
+            } else {
+                &pkg.arch
+            };
+            // in lenient mode the value is dropped so the result stays valid
+            if let Err(e) = self.check_arch(pkg_arch, key, arch) {
+                return self.recover(Err(e));
+            }
         }
 
+        if self.options.strict && arch.is_none() {
+            self.check_duplicate(key)?;
+        }
+
+        let mut in_base = false;
         if self.match_pkgbase(key, value) {
-            self.check_not_arch_specific(key_arch, arch)?;
-            self.check_key_after_pkgname(key_arch)?;
+            self.check_not_arch_specific(key, arch)?;
+            self.recover(self.check_key_after_pkgname(key, arch))?;
+            in_base = true;
         } else if self.match_pkgbase_arch(key, arch, value) {
-            self.check_key_after_pkgname(key_arch)?;
+            self.recover(self.check_key_after_pkgname(key, arch))?;
+            in_base = true;
         } else if self.match_pkg(key, value) {
-            self.check_not_arch_specific(key_arch, arch)?;
+            self.check_not_arch_specific(key, arch)?;
+            self.add_provenance(key, arch);
+        } else if self.match_pkg_arch(key, arch, value) {
+            self.add_provenance(key, arch);
         } else {
-            self.match_pkg_arch(key, arch, value);
+            if self.options.strict {
+                return Err(ErrorKind::UnknownKey(key_arch(key, arch)));
+            }
+            self.push_extra(key, arch, value);
         }
 
+        self.push_span(key, arch, in_base);
         Ok(())
     }
 
-    fn match_pkgbase(&mut self, key: &str, value: &str) -> bool {
+    // records that the current package section set the field
+    fn add_provenance(&mut self, key: &'a str, arch: Option<&'a str>) {
+        if self.has_pkg {
+            self.last_pkg()
+                .add_provenance(key, arch, Provenance::Overridden);
+        }
+    }
+
+    fn push_extra(&mut self, key: &'a str, arch: Option<&'a str>, value: &'a str) {
+        let extra = match self.srcinfo.pkgs.last_mut() {
+            Some(pkg) => &mut pkg.extra,
+            None => &mut self.srcinfo.base.extra,
+        };
+
+        append_arch_strings(extra.entry(key).or_default(), arch, value);
+    }
+
+    fn match_pkgbase(&mut self, key: &str, value: &'a str) -> bool {
         let base = &mut self.srcinfo.base;
         match key {
-            "pkgver" => base.pkgver = value.to_string(),
-            "pkgrel" => base.pkgrel = value.to_string(),
-            "epoch" => base.epoch = Some(value.to_string()),
-            "validpgpkeys" => base.valid_pgp_keys.push(value.to_string()),
-            "noextract" => base.no_extract.push(value.to_string()),
+            "pkgver" => base.pkgver = value,
+            "pkgrel" => base.pkgrel = value,
+            "epoch" => base.epoch = Some(value),
+            "validpgpkeys" => base.valid_pgp_keys.push(value),
+            "noextract" => base.no_extract.push(value),
             _ => return false,
         }
 
         true
     }
 
-    fn match_pkgbase_arch(&mut self, key: &str, arch: Option<&str>, value: &str) -> bool {
+    fn match_pkgbase_arch(&mut self, key: &str, arch: Option<&'a str>, value: &'a str) -> bool {
         let base = &mut self.srcinfo.base;
         match key {
             "source" => append_arch_strings(&mut base.source, arch, value),
+            "cksums" => append_arch_strings(&mut base.cksums, arch, value),
             "md5sums" => append_arch_strings(&mut base.md5sums, arch, value),
             "sha1sums" => append_arch_strings(&mut base.sha1sums, arch, value),
             "sha224sums" => append_arch_strings(&mut base.sha224sums, arch, value),
@@ -310,26 +484,26 @@ impl Parser {
         true
     }
 
-    fn match_pkg(&mut self, key: &str, value: &str) -> bool {
+    fn match_pkg(&mut self, key: &str, value: &'a str) -> bool {
         let pkg = self.last_pkg();
 
         match key {
-            "pkgdesc" => pkg.pkgdesc = Some(value.to_string()),
-            "url" => pkg.url = Some(value.to_string()),
-            "license" => pkg.license.push(value.to_string()),
-            "install" => pkg.install = Some(value.to_string()),
-            "changelog" => pkg.changelog = Some(value.to_string()),
-            "groups" => pkg.groups.push(value.to_string()),
-            "arch" => pkg.arch.push(value.to_string()),
-            "backup" => pkg.backup.push(value.to_string()),
-            "options" => pkg.options.push(value.to_string()),
+            "pkgdesc" => pkg.pkgdesc = Some(value),
+            "url" => pkg.url = Some(value),
+            "license" => pkg.license.push(value),
+            "install" => pkg.install = Some(value),
+            "changelog" => pkg.changelog = Some(value),
+            "groups" => pkg.groups.push(value),
+            "arch" => pkg.arch.push(value),
+            "backup" => pkg.backup.push(value),
+            "options" => pkg.options.push(value),
             _ => return false,
         }
 
         true
     }
 
-    fn match_pkg_arch(&mut self, key: &str, arch: Option<&str>, value: &str) -> bool {
+    fn match_pkg_arch(&mut self, key: &str, arch: Option<&'a str>, value: &'a str) -> bool {
         let pkg = self.last_pkg();
 
         match key {
@@ -348,61 +522,32 @@ impl Parser {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::ArchVec;
-
-    #[test]
-    fn test_split_pair() {
-        assert_eq!(split_pair("a=b").unwrap(), ("a", Some("b")));
-        assert_eq!(split_pair("a==b").unwrap(), ("a", Some("=b")));
-        assert_eq!(split_pair("a= b").unwrap(), ("a", Some("b")));
-        assert_eq!(split_pair("a =b").unwrap(), ("a", Some("b")));
-        assert_eq!(split_pair("a = b").unwrap(), ("a", Some("b")));
-        assert_eq!(split_pair(" a = b ").unwrap(), ("a", Some("b")));
-        assert_eq!(split_pair("\ta\t= b").unwrap(), ("a", Some("b")));
-        assert_eq!(split_pair("a=").unwrap(), ("a", None));
-        assert_eq!(split_pair(" a =").unwrap(), ("a", None));
-
-        let err = split_pair("a").unwrap_err();
-        match err {
-            ErrorKind::EmptyValue(ref key) => assert_eq!(key, "a"),
-            _ => panic!("{:?}", err),
-        }
-
-        assert!(split_pair("=b").is_err());
-    }
-
-    #[test]
-    fn test_split_key_arch() {
-        assert_eq!(split_key_arch("a_b"), ("a", Some("b")));
-        assert_eq!(split_key_arch("a_b_c"), ("a", Some("b_c")));
-        assert_eq!(split_key_arch("a"), ("a", None));
-    }
 
     #[test]
     fn test_append_arch_strings() {
-        let mut arch_strings = ArchVecs::from(vec![ArchVec::from("x86_64")]);
+        let mut arch_strings =
+            ArchVecsRef::from(vec![ArchVecRef::with_values(Some("x86_64"), vec![])]);
         append_arch_strings(&mut arch_strings, Some("arm"), "foo");
 
         assert_eq!(
             arch_strings,
-            ArchVecs::from(vec![
-                ArchVec::from("x86_64"),
-                ArchVec::with_values(Some("arm"), vec!["foo".to_string()]),
+            ArchVecsRef::from(vec![
+                ArchVecRef::with_values(Some("x86_64"), vec![]),
+                ArchVecRef::with_values(Some("arm"), vec!["foo"]),
             ])
         );
 
-        let mut arch_strings = ArchVecs::from(vec![ArchVec::from("x86_64")]);
+        let mut arch_strings =
+            ArchVecsRef::from(vec![ArchVecRef::with_values(Some("x86_64"), vec![])]);
         append_arch_strings(&mut arch_strings, Some("x86_64"), "foo");
 
         assert_eq!(
             arch_strings,
-            ArchVecs::from(vec![ArchVec::with_values(
-                Some("x86_64"),
-                vec!["foo".to_string()]
-            )])
+            ArchVecsRef::from(vec![ArchVecRef::with_values(Some("x86_64"), vec!["foo"])])
         );
 
-        let mut arch_strings = ArchVecs::from(vec![ArchVec::from("x86_64")]);
+        let mut arch_strings =
+            ArchVecsRef::from(vec![ArchVecRef::with_values(Some("x86_64"), vec![])]);
         append_arch_strings(&mut arch_strings, Some("x86_64"), "foo");
         append_arch_strings(&mut arch_strings, Some("x86_64"), "bar");
         append_arch_strings(&mut arch_strings, Some("x86_64"), "a");
@@ -410,18 +555,14 @@ mod tests {
 
         assert_eq!(
             arch_strings,
-            ArchVecs::from(vec![ArchVec::with_values(
+            ArchVecsRef::from(vec![ArchVecRef::with_values(
                 Some("x86_64"),
-                vec![
-                    "foo".to_string(),
-                    "bar".to_string(),
-                    "a".to_string(),
-                    "b".to_string()
-                ]
+                vec!["foo", "bar", "a", "b"]
             )])
         );
 
-        let mut arch_strings = ArchVecs::from(vec![ArchVec::from("x86_64")]);
+        let mut arch_strings =
+            ArchVecsRef::from(vec![ArchVecRef::with_values(Some("x86_64"), vec![])]);
         append_arch_strings(&mut arch_strings, Some("x86_64"), "foo");
         append_arch_strings(&mut arch_strings, Some("arm"), "bar");
         append_arch_strings(&mut arch_strings, Some("x86_64"), "a");
@@ -429,9 +570,9 @@ mod tests {
 
         assert_eq!(
             arch_strings,
-            ArchVecs::from(vec![
-                ArchVec::with_values(Some("x86_64"), vec!["foo".to_string(), "a".to_string()]),
-                ArchVec::with_values(Some("arm"), vec!["bar".to_string(), "b".to_string()]),
+            ArchVecsRef::from(vec![
+                ArchVecRef::with_values(Some("x86_64"), vec!["foo", "a"]),
+                ArchVecRef::with_values(Some("arm"), vec!["bar", "b"]),
             ])
         );
     }
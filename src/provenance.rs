@@ -0,0 +1,389 @@
+use std::cmp::Ordering;
+use std::hash::{Hash, Hasher};
+use std::ops::{Deref, DerefMut};
+
+use crate::archvec::{ArchVec, ArchVecRef};
+use crate::srcinfo::Package;
+use crate::srcinfo_ref::PackageRef;
+
+// inherits every package field from the global package, this is the one place that decides
+// what a package inherits and is used by the parser, PackageBuilder and Srcinfo::edit_global
+macro_rules! inherit_fields {
+    ($pkg:expr, $global:expr) => {{
+        use crate::provenance::{inherit, inherit_arch};
+        let (pkg, global) = ($pkg, $global);
+        inherit(
+            &pkg.provenance,
+            "pkgdesc",
+            &mut pkg.pkgdesc,
+            &global.pkgdesc,
+        );
+        inherit(&pkg.provenance, "arch", &mut pkg.arch, &global.arch);
+        inherit(&pkg.provenance, "url", &mut pkg.url, &global.url);
+        inherit(
+            &pkg.provenance,
+            "license",
+            &mut pkg.license,
+            &global.license,
+        );
+        inherit(&pkg.provenance, "groups", &mut pkg.groups, &global.groups);
+        inherit_arch(
+            &pkg.provenance,
+            "depends",
+            &mut pkg.depends.vecs,
+            &global.depends.vecs,
+        );
+        inherit_arch(
+            &pkg.provenance,
+            "optdepends",
+            &mut pkg.optdepends.vecs,
+            &global.optdepends.vecs,
+        );
+        inherit_arch(
+            &pkg.provenance,
+            "provides",
+            &mut pkg.provides.vecs,
+            &global.provides.vecs,
+        );
+        inherit_arch(
+            &pkg.provenance,
+            "conflicts",
+            &mut pkg.conflicts.vecs,
+            &global.conflicts.vecs,
+        );
+        inherit_arch(
+            &pkg.provenance,
+            "replaces",
+            &mut pkg.replaces.vecs,
+            &global.replaces.vecs,
+        );
+        inherit(&pkg.provenance, "backup", &mut pkg.backup, &global.backup);
+        inherit(
+            &pkg.provenance,
+            "options",
+            &mut pkg.options,
+            &global.options,
+        );
+        inherit(
+            &pkg.provenance,
+            "install",
+            &mut pkg.install,
+            &global.install,
+        );
+        inherit(
+            &pkg.provenance,
+            "changelog",
+            &mut pkg.changelog,
+            &global.changelog,
+        );
+    }};
+}
+
+pub(crate) use inherit_fields;
+
+/// Where the value of a package field came from.
+///
+/// Architecture specific fields have a provenance for each architecture, `depends` and
+/// `depends_x86_64` are tracked separately.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Provenance {
+    /// The field was not set in the package section and is inherited from the pkgbase
+    #[default]
+    Inherited,
+    /// The field was set in the package section
+    Overridden,
+    /// The field was cleared in the package section with an empty value such as `depends =`
+    Cleared,
+}
+
+// the provenance of the fields a package set or cleared, sorted by key and architecture
+//
+// the provenance records how a package was written and not what it contains, so it is left
+// out of comparisons and hashing: a package is equal to the same package written out in full
+//
+// Package holds owned keys and PackageRef borrows them from the input
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
+pub(crate) struct Provenances<K = String>(pub(crate) Vec<(K, Option<K>, Provenance)>);
+
+impl<K> Deref for Provenances<K> {
+    type Target = Vec<(K, Option<K>, Provenance)>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<K> DerefMut for Provenances<K> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl<K> PartialEq for Provenances<K> {
+    fn eq(&self, _: &Self) -> bool {
+        true
+    }
+}
+
+impl<K> Eq for Provenances<K> {}
+
+impl<K> PartialOrd for Provenances<K> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<K> Ord for Provenances<K> {
+    fn cmp(&self, _: &Self) -> Ordering {
+        Ordering::Equal
+    }
+}
+
+impl<K> Hash for Provenances<K> {
+    fn hash<H: Hasher>(&self, _: &mut H) {}
+}
+
+fn position<K: AsRef<str>>(
+    list: &[(K, Option<K>, Provenance)],
+    key: &str,
+    arch: Option<&str>,
+) -> Result<usize, usize> {
+    list.binary_search_by(|(k, a, _)| {
+        (k.as_ref(), a.as_ref().map(|a| a.as_ref())).cmp(&(key, arch))
+    })
+}
+
+fn get<K: AsRef<str>>(
+    list: &[(K, Option<K>, Provenance)],
+    key: &str,
+    arch: Option<&str>,
+) -> Provenance {
+    match position(list, key, arch) {
+        Ok(i) => list[i].2,
+        Err(_) => Provenance::Inherited,
+    }
+}
+
+// an Inherited field takes the global value, an Overridden or Cleared field keeps its own
+pub(crate) fn inherit<K: AsRef<str>, T: Clone>(
+    list: &[(K, Option<K>, Provenance)],
+    key: &str,
+    value: &mut T,
+    global: &T,
+) {
+    if get(list, key, None) == Provenance::Inherited {
+        value.clone_from(global);
+    }
+}
+
+// the same for an architecture specific field where each architecture is inherited on its own
+pub(crate) fn inherit_arch<K: AsRef<str>, V: ArchKey + Clone>(
+    list: &[(K, Option<K>, Provenance)],
+    key: &str,
+    vecs: &mut Vec<V>,
+    global: &[V],
+) {
+    vecs.retain(|v| get(list, key, v.arch_key()) != Provenance::Inherited);
+    for vec in global {
+        if get(list, key, vec.arch_key()) == Provenance::Inherited {
+            vecs.push(vec.clone());
+        }
+    }
+}
+
+// lets inherit_arch work with both ArchVec and ArchVecRef
+pub(crate) trait ArchKey {
+    fn arch_key(&self) -> Option<&str>;
+}
+
+impl ArchKey for ArchVec {
+    fn arch_key(&self) -> Option<&str> {
+        self.arch()
+    }
+}
+
+impl ArchKey for ArchVecRef<'_> {
+    fn arch_key(&self) -> Option<&str> {
+        self.arch()
+    }
+}
+
+impl Package {
+    /// Gets where the value of a field came from.
+    ///
+    /// The key is the name of the field without the architecture, such as "depends". Fields
+    /// of [`Srcinfo::pkg`](crate::Srcinfo::pkg) are always [`Provenance::Inherited`].
+    ///
+    /// ```
+    /// # use srcinfo::Error;
+    /// use srcinfo::{Provenance, Srcinfo};
+    ///
+    /// # fn test() -> Result<(), Error> {
+    /// let srcinfo: Srcinfo = "
+    /// pkgbase = example
+    /// pkgver = 1.5.0
+    /// pkgrel = 5
+    /// arch = x86_64
+    /// depends = foo
+    /// depends_x86_64 = bar
+    ///
+    /// pkgname = example
+    /// depends = foo
+    /// depends_x86_64 =".parse()?;
+    ///
+    /// let pkg = srcinfo.pkg("example").unwrap();
+    /// assert_eq!(pkg.provenance("depends", None), Provenance::Overridden);
+    /// assert_eq!(pkg.provenance("depends", Some("x86_64")), Provenance::Cleared);
+    /// assert_eq!(pkg.provenance("pkgdesc", None), Provenance::Inherited);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn provenance(&self, key: &str, arch: Option<&str>) -> Provenance {
+        get(&self.provenance, key, arch)
+    }
+
+    /// Sets where the value of a field came from.
+    ///
+    /// This does not change the value of the field.
+    pub fn set_provenance<K: Into<String>, A: Into<String>>(
+        &mut self,
+        key: K,
+        arch: Option<A>,
+        provenance: Provenance,
+    ) {
+        let (key, arch) = (key.into(), arch.map(Into::into));
+        match (
+            position(&self.provenance, &key, arch.as_deref()),
+            provenance,
+        ) {
+            (Ok(i), Provenance::Inherited) => {
+                self.provenance.remove(i);
+            }
+            (Ok(i), provenance) => self.provenance[i].2 = provenance,
+            (Err(_), Provenance::Inherited) => (),
+            (Err(i), provenance) => self.provenance.insert(i, (key, arch, provenance)),
+        }
+    }
+}
+
+impl<'a> PackageRef<'a> {
+    /// Gets where the value of a field came from.
+    ///
+    /// See [`Package::provenance`].
+    pub fn provenance(&self, key: &str, arch: Option<&str>) -> Provenance {
+        get(&self.provenance, key, arch)
+    }
+
+    // records the provenance of a field unless the section already set or cleared it
+    pub(crate) fn add_provenance(
+        &mut self,
+        key: &'a str,
+        arch: Option<&'a str>,
+        provenance: Provenance,
+    ) {
+        if let Err(i) = position(&self.provenance, key, arch) {
+            self.provenance.insert(i, (key, arch, provenance));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Srcinfo, SrcinfoRef};
+    use std::hash::DefaultHasher;
+
+    #[test]
+    fn test_provenance() {
+        let srcinfo: Srcinfo = "
+pkgbase = a
+pkgver = 1
+pkgrel = 1
+arch = x86_64
+pkgdesc = desc
+depends = foo
+depends_x86_64 = bar
+
+pkgname = a
+pkgdesc = desc
+depends_x86_64 =
+depends_x86_64 = baz
+url =
+
+pkgname = b"
+            .parse()
+            .unwrap();
+
+        let a = srcinfo.pkg("a").unwrap();
+        assert_eq!(a.provenance("pkgdesc", None), Provenance::Overridden);
+        assert_eq!(a.provenance("depends", None), Provenance::Inherited);
+        assert_eq!(a.provenance("depends", Some("x86_64")), Provenance::Cleared);
+        assert_eq!(a.provenance("url", None), Provenance::Cleared);
+        assert!(a.depends.get(Some("x86_64")).is_none());
+
+        let b = srcinfo.pkg("b").unwrap();
+        assert!(b.provenance.is_empty());
+        assert!(srcinfo.pkg.provenance.is_empty());
+    }
+
+    #[test]
+    fn test_set_provenance() {
+        let mut pkg = Package::default();
+        pkg.set_provenance("depends", Some("x86_64"), Provenance::Cleared);
+        pkg.set_provenance("depends", None::<&str>, Provenance::Overridden);
+        pkg.set_provenance("arch", None::<&str>, Provenance::Overridden);
+        assert_eq!(
+            pkg.provenance.0,
+            vec![
+                ("arch".to_string(), None, Provenance::Overridden),
+                ("depends".to_string(), None, Provenance::Overridden),
+                (
+                    "depends".to_string(),
+                    Some("x86_64".to_string()),
+                    Provenance::Cleared
+                ),
+            ]
+        );
+
+        pkg.set_provenance("depends", None::<&str>, Provenance::Inherited);
+        assert_eq!(pkg.provenance("depends", None), Provenance::Inherited);
+        assert_eq!(pkg.provenance.len(), 2);
+    }
+
+    #[test]
+    fn test_provenance_eq() {
+        let base = "
+pkgbase = a
+pkgver = 1
+pkgrel = 1
+pkgdesc = desc
+
+pkgname = a
+";
+        let inherited: Srcinfo = base.parse().unwrap();
+        let overridden: Srcinfo = format!("{}pkgdesc = desc", base).parse().unwrap();
+
+        let (a, b) = (&inherited.pkgs[0], &overridden.pkgs[0]);
+        assert_ne!(a.provenance("pkgdesc", None), b.provenance("pkgdesc", None));
+        assert_eq!(a, b);
+        assert_eq!(a.cmp(b), Ordering::Equal);
+
+        let hash = |pkg: &Package| {
+            let mut hasher = DefaultHasher::new();
+            pkg.hash(&mut hasher);
+            hasher.finish()
+        };
+        assert_eq!(hash(a), hash(b));
+
+        let overridden = format!("{}pkgdesc = desc", base);
+        let inherited = SrcinfoRef::parse(base).unwrap();
+        let overridden = SrcinfoRef::parse(&overridden).unwrap();
+        let (a, b) = (&inherited.pkgs[0], &overridden.pkgs[0]);
+        assert_ne!(a.provenance("pkgdesc", None), b.provenance("pkgdesc", None));
+        assert_eq!(a, b);
+        assert_eq!(a.cmp(b), Ordering::Equal);
+    }
+}
@@ -0,0 +1,222 @@
+use crate::archvec::ArchVecs;
+use crate::srcinfo::{Package, Srcinfo};
+
+fn strs(v: &[String]) -> Vec<&str> {
+    v.iter().map(|s| s.as_str()).collect()
+}
+
+fn flatten<'a>(v: &'a ArchVecs, arch: &str) -> Vec<&'a str> {
+    v.arch(arch).collect()
+}
+
+/// A [`Srcinfo`] resolved for a single architecture.
+///
+/// Architecture specific fields are flattened into a single list holding both the values for the
+/// architecture and the values that apply to every architecture, the same as [`ArchVecs::arch`].
+/// Created with [`Srcinfo::for_arch`].
+#[allow(missing_docs)]
+#[derive(Clone, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[non_exhaustive]
+pub struct ResolvedSrcinfo<'a> {
+    pub pkgbase: &'a str,
+    pub pkgver: &'a str,
+    pub pkgrel: &'a str,
+    pub epoch: Option<&'a str>,
+    pub source: Vec<&'a str>,
+    pub valid_pgp_keys: Vec<&'a str>,
+    pub no_extract: Vec<&'a str>,
+    pub cksums: Vec<&'a str>,
+    pub md5sums: Vec<&'a str>,
+    pub sha1sums: Vec<&'a str>,
+    pub sha224sums: Vec<&'a str>,
+    pub sha256sums: Vec<&'a str>,
+    pub sha384sums: Vec<&'a str>,
+    pub sha512sums: Vec<&'a str>,
+    pub b2sums: Vec<&'a str>,
+    pub makedepends: Vec<&'a str>,
+    pub checkdepends: Vec<&'a str>,
+    /// The packages that can be built for the architecture
+    pub pkgs: Vec<ResolvedPackage<'a>>,
+}
+
+/// A [`Package`] resolved for a single architecture.
+#[allow(missing_docs)]
+#[derive(Clone, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[non_exhaustive]
+pub struct ResolvedPackage<'a> {
+    pub pkgname: &'a str,
+    pub pkgdesc: Option<&'a str>,
+    /// The architecture the package is built as, either the requested architecture or "any"
+    pub arch: &'a str,
+    pub url: Option<&'a str>,
+    pub license: Vec<&'a str>,
+    pub groups: Vec<&'a str>,
+    pub depends: Vec<&'a str>,
+    pub optdepends: Vec<&'a str>,
+    pub provides: Vec<&'a str>,
+    pub conflicts: Vec<&'a str>,
+    pub replaces: Vec<&'a str>,
+    pub backup: Vec<&'a str>,
+    pub options: Vec<&'a str>,
+    pub install: Option<&'a str>,
+    pub changelog: Option<&'a str>,
+}
+
+impl<'a> ResolvedPackage<'a> {
+    fn new(pkg: &'a Package, arch: &str) -> Option<ResolvedPackage<'a>> {
+        // like makepkg an arch=any package is built on every architecture
+        let pkg_arch = pkg
+            .arch
+            .iter()
+            .find(|a| *a == arch)
+            .or_else(|| pkg.arch.iter().find(|a| *a == "any"))?;
+
+        Some(ResolvedPackage {
+            pkgname: &pkg.pkgname,
+            pkgdesc: pkg.pkgdesc.as_deref(),
+            arch: pkg_arch,
+            url: pkg.url.as_deref(),
+            license: strs(&pkg.license),
+            groups: strs(&pkg.groups),
+            depends: flatten(&pkg.depends, arch),
+            optdepends: flatten(&pkg.optdepends, arch),
+            provides: flatten(&pkg.provides, arch),
+            conflicts: flatten(&pkg.conflicts, arch),
+            replaces: flatten(&pkg.replaces, arch),
+            backup: strs(&pkg.backup),
+            options: strs(&pkg.options),
+            install: pkg.install.as_deref(),
+            changelog: pkg.changelog.as_deref(),
+        })
+    }
+}
+
+impl Srcinfo {
+    /// Resolves the Srcinfo for a single architecture.
+    ///
+    /// Packages whose arch does not contain the architecture or "any" are left out.
+    ///
+    /// ```
+    /// # use srcinfo::Error;
+    /// use srcinfo::Srcinfo;
+    ///
+    /// # fn test() -> Result<(), Error> {
+    /// let srcinfo: Srcinfo = "
+    /// pkgbase = example
+    /// pkgver = 1.5.0
+    /// pkgrel = 5
+    /// arch = x86_64
+    /// arch = aarch64
+    /// depends = foo
+    /// depends_x86_64 = bar
+    ///
+    /// pkgname = example
+    ///
+    /// pkgname = example-x86
+    /// arch = x86_64".parse()?;
+    ///
+    /// let resolved = srcinfo.for_arch("aarch64");
+    /// assert_eq!(resolved.pkgs.len(), 1);
+    /// assert_eq!(resolved.pkgs[0].depends, vec!["foo"]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn for_arch(&self, arch: &str) -> ResolvedSrcinfo<'_> {
+        let base = &self.base;
+
+        ResolvedSrcinfo {
+            pkgbase: &base.pkgbase,
+            pkgver: &base.pkgver,
+            pkgrel: &base.pkgrel,
+            epoch: base.epoch.as_deref(),
+            source: flatten(&base.source, arch),
+            valid_pgp_keys: strs(&base.valid_pgp_keys),
+            no_extract: strs(&base.no_extract),
+            cksums: flatten(&base.cksums, arch),
+            md5sums: flatten(&base.md5sums, arch),
+            sha1sums: flatten(&base.sha1sums, arch),
+            sha224sums: flatten(&base.sha224sums, arch),
+            sha256sums: flatten(&base.sha256sums, arch),
+            sha384sums: flatten(&base.sha384sums, arch),
+            sha512sums: flatten(&base.sha512sums, arch),
+            b2sums: flatten(&base.b2sums, arch),
+            makedepends: flatten(&base.makedepends, arch),
+            checkdepends: flatten(&base.checkdepends, arch),
+            pkgs: self
+                .pkgs
+                .iter()
+                .filter_map(|pkg| ResolvedPackage::new(pkg, arch))
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_for_arch() {
+        let srcinfo = Srcinfo::from_path("tests/srcinfo/libc++").unwrap();
+        let resolved = srcinfo.for_arch("x86_64");
+
+        assert_eq!(resolved.pkgbase, "libc++");
+        assert_eq!(
+            resolved.makedepends,
+            srcinfo.makedepends().arch("x86_64").collect::<Vec<_>>()
+        );
+        assert_eq!(resolved.pkgs.len(), srcinfo.pkgs().len());
+        for (resolved, pkg) in resolved.pkgs.iter().zip(srcinfo.pkgs()) {
+            assert_eq!(resolved.pkgname, pkg.pkgname);
+            assert_eq!(resolved.arch, "x86_64");
+            assert_eq!(
+                resolved.depends,
+                pkg.depends.arch("x86_64").collect::<Vec<_>>()
+            );
+        }
+
+        assert!(srcinfo.for_arch("aarch64").pkgs.is_empty());
+    }
+
+    #[test]
+    fn test_for_arch_any() {
+        let srcinfo: Srcinfo = "
+pkgbase = a
+pkgver = 1
+pkgrel = 1
+arch = x86_64
+arch = i686
+source = a.tar.gz
+source_x86_64 = b.tar.gz
+sha256sums = SKIP
+sha256sums_x86_64 = SKIP
+
+pkgname = a
+depends_i686 = lib32
+
+pkgname = a-docs
+arch = any
+
+pkgname = a-64
+arch = x86_64"
+            .parse()
+            .unwrap();
+
+        let resolved = srcinfo.for_arch("i686");
+        assert_eq!(resolved.source, vec!["a.tar.gz"]);
+        let pkgs = resolved.pkgs.iter().map(|p| (p.pkgname, p.arch));
+        assert_eq!(
+            pkgs.collect::<Vec<_>>(),
+            vec![("a", "i686"), ("a-docs", "any")]
+        );
+        assert_eq!(resolved.pkgs[0].depends, vec!["lib32"]);
+
+        let resolved = srcinfo.for_arch("x86_64");
+        assert_eq!(resolved.source, vec!["a.tar.gz", "b.tar.gz"]);
+        assert_eq!(resolved.sha256sums, vec!["SKIP", "SKIP"]);
+        assert_eq!(resolved.pkgs.len(), 3);
+        assert!(resolved.pkgs[0].depends.is_empty());
+    }
+}
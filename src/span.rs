@@ -0,0 +1,77 @@
+/// The location of a value in the parsed input.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Span {
+    /// The line number, starting at 1
+    pub line: usize,
+    /// The column in characters, starting at 1
+    pub column: usize,
+    /// The byte offset from the start of the input
+    pub offset: usize,
+    /// The length in bytes
+    pub len: usize,
+}
+
+impl Span {
+    /// The byte range of the span in the input
+    pub fn range(&self) -> std::ops::Range<usize> {
+        self.offset..self.offset + self.len
+    }
+}
+
+/// The locations of the values inside of a pkgbase or pkgname section.
+#[derive(Clone, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct SectionSpans {
+    /// The location of the pkgbase or pkgname value that starts the section
+    pub header: Span,
+    pub(crate) fields: Vec<(String, Option<String>, Vec<Span>)>,
+}
+
+impl SectionSpans {
+    /// Gets the locations of every value of a key and architecture, in the order they were
+    /// declared.
+    ///
+    /// For fields that hold a list the spans line up with the values of the matching
+    /// [`ArchVec`](crate::ArchVec) or Vec. For fields that hold a single value the last span is
+    /// the one in effect.
+    pub fn get(&self, key: &str, arch: Option<&str>) -> &[Span] {
+        self.fields
+            .iter()
+            .find(|(k, a, _)| k == key && a.as_deref() == arch)
+            .map(|(_, _, spans)| spans.as_slice())
+            .unwrap_or_default()
+    }
+
+    /// Returns an iterator over every key, architecture and the locations of its values.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, Option<&str>, &[Span])> {
+        self.fields
+            .iter()
+            .map(|(k, a, spans)| (k.as_str(), a.as_deref(), spans.as_slice()))
+    }
+
+    pub(crate) fn push(&mut self, key: &str, arch: Option<&str>, span: Span) {
+        if let Some((_, _, spans)) = self
+            .fields
+            .iter_mut()
+            .find(|(k, a, _)| k == key && a.as_deref() == arch)
+        {
+            spans.push(span);
+        } else {
+            self.fields
+                .push((key.to_string(), arch.map(str::to_string), vec![span]));
+        }
+    }
+}
+
+/// The locations of every value in a parsed .SRCINFO.
+///
+/// Spans are only collected when parsing with [`Srcinfo::parse_with_spans`](crate::Srcinfo::parse_with_spans).
+///
+/// Values are recorded in the section they were declared in. Package fields inherited
+/// from the pkgbase section have their spans in [`Spans::base`].
+#[derive(Clone, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Spans {
+    /// The pkgbase section
+    pub base: SectionSpans,
+    /// The pkgname sections, in the same order as [`Srcinfo::pkgs`](crate::Srcinfo::pkgs)
+    pub pkgs: Vec<SectionSpans>,
+}
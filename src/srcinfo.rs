@@ -1,11 +1,14 @@
-use std::fs::File;
-use std::io::{BufRead, BufReader};
+use std::collections::BTreeMap;
+use std::io::BufRead;
 use std::path::Path;
 use std::str::FromStr;
 
 use crate::archvec::ArchVecs;
 use crate::error::Error;
-use crate::parse::Parser;
+use crate::generator::Generator;
+use crate::options::ParseOptions;
+use crate::provenance::Provenances;
+use crate::span::Spans;
 
 macro_rules! get {
     ($fn:ident, $( $field:ident ).+, $typ:ty) => {
@@ -24,6 +27,16 @@ macro_rules! geto {
     };
 }
 
+pub(crate) use {get, geto};
+
+// the full version, "epoch:pkgver-pkgrel" or "pkgver-pkgrel" when the epoch is not set
+pub(crate) fn full_version(epoch: Option<&str>, pkgver: &str, pkgrel: &str) -> String {
+    match epoch {
+        Some(epoch) => format!("{}:{}-{}", epoch, pkgver, pkgrel),
+        None => format!("{}-{}", pkgver, pkgrel),
+    }
+}
+
 /// The fields from a .SRCINFO that only apply to the pkgbase.
 #[allow(missing_docs)]
 #[derive(Clone, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
@@ -37,6 +50,8 @@ pub struct PackageBase {
     pub source: ArchVecs,
     pub valid_pgp_keys: Vec<String>,
     pub no_extract: Vec<String>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub cksums: ArchVecs,
     pub md5sums: ArchVecs,
     pub sha1sums: ArchVecs,
     pub sha224sums: ArchVecs,
@@ -46,6 +61,9 @@ pub struct PackageBase {
     pub b2sums: ArchVecs,
     pub makedepends: ArchVecs,
     pub checkdepends: ArchVecs,
+    /// Unknown keys from the pkgbase section
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub extra: BTreeMap<String, ArchVecs>,
 }
 
 /// The fields from a .SRCINFO that are unique to each package.
@@ -69,6 +87,15 @@ pub struct Package {
     pub options: Vec<String>,
     pub install: Option<String>,
     pub changelog: Option<String>,
+    /// Unknown keys from the pkgname section
+    ///
+    /// Unknown keys from the pkgbase section are stored in [`PackageBase::extra`] and are not
+    /// inherited.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub extra: BTreeMap<String, ArchVecs>,
+    // the fields that the package section set or cleared itself, see Package::provenance
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub(crate) provenance: Provenances,
 }
 
 impl Package {
@@ -87,6 +114,7 @@ impl Package {
     get!(options, options, &[String]);
     geto!(install, install, Option<&str>);
     geto!(changelog, changelog, Option<&str>);
+    get!(extra, extra, &BTreeMap<String, ArchVecs>);
 }
 
 /// A complete representation of a .SRCINFO file.
@@ -108,7 +136,7 @@ impl FromStr for Srcinfo {
     type Err = Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        Parser::parse(s.as_bytes())
+        ParseOptions::default().parse_str(s)
     }
 }
 
@@ -135,7 +163,37 @@ impl Srcinfo {
     /// # }
     /// ```
     pub fn from_buf<T: BufRead>(b: T) -> Result<Srcinfo, Error> {
-        Parser::parse(b)
+        ParseOptions::default().parse_buf(b)
+    }
+
+    /// Parse bytes that may not be valid UTF-8.
+    ///
+    /// Invalid UTF-8 fails with [`ErrorKind::InvalidUtf8`](crate::ErrorKind::InvalidUtf8), which
+    /// carries the byte offset, and the error line holds the line number. To replace invalid
+    /// UTF-8 instead, use [`ParseOptions::lossy_utf8`].
+    ///
+    /// ```
+    /// use srcinfo::{ErrorKind, ParseOptions, Srcinfo};
+    ///
+    /// let buf = b"
+    /// pkgbase = example
+    /// pkgver = 1.5.0
+    /// pkgrel = 5
+    ///
+    /// pkgname = example
+    /// pkgdesc = caf\xe9";
+    ///
+    /// let err = Srcinfo::from_bytes(buf).unwrap_err();
+    /// assert!(matches!(err.kind, ErrorKind::InvalidUtf8(77)));
+    /// assert_eq!(err.line.unwrap().number, 7);
+    ///
+    /// let mut options = ParseOptions::new();
+    /// options.lossy_utf8 = true;
+    /// let srcinfo = options.parse_bytes(buf).unwrap();
+    /// assert_eq!(srcinfo.pkgs()[0].pkgdesc(), Some("caf\u{fffd}"));
+    /// ```
+    pub fn from_bytes(b: &[u8]) -> Result<Srcinfo, Error> {
+        ParseOptions::default().parse_bytes(b)
     }
 
     /// Parse the file at a given path.
@@ -152,9 +210,63 @@ impl Srcinfo {
     /// # }
     /// ```
     pub fn from_path<P: AsRef<Path>>(s: P) -> Result<Srcinfo, Error> {
-        let file = File::open(s)?;
-        let buf = BufReader::new(file);
-        Parser::parse(buf)
+        ParseOptions::default().parse_path(s)
+    }
+
+    /// Parse a BufRead, collecting every error instead of stopping at the first one.
+    ///
+    /// Lines that fail to parse are skipped and parsing continues with the next line.
+    /// The returned Srcinfo contains everything that could be parsed and is only
+    /// meaningful if the list of errors is empty.
+    ///
+    /// ```
+    /// use srcinfo::Srcinfo;
+    ///
+    /// let buf = "
+    /// pkgbase = example
+    /// pkgver = 1.5.0
+    /// = 5
+    ///
+    /// pkgname = example".as_bytes();
+    ///
+    /// let (srcinfo, errors) = Srcinfo::parse_with_diagnostics(buf);
+    /// assert_eq!(srcinfo.pkgbase(), "example");
+    ///
+    /// for error in &errors {
+    ///     println!("{}", error);
+    /// }
+    /// assert_eq!(errors.len(), 2);
+    /// ```
+    pub fn parse_with_diagnostics<T: BufRead>(b: T) -> (Srcinfo, Vec<Error>) {
+        ParseOptions::default().parse_with_diagnostics(b)
+    }
+
+    /// Parse a BufRead, also recording the location of every value.
+    ///
+    /// ```
+    /// # use srcinfo::Error;
+    /// use srcinfo::Srcinfo;
+    ///
+    /// # fn test() -> Result<(), Error> {
+    /// let buf = "pkgbase = example
+    /// \tpkgver = 1.5.0
+    /// \tpkgrel = 5
+    /// \tarch = x86_64
+    ///
+    /// pkgname = example
+    /// \tdepends_x86_64 = foo";
+    ///
+    /// let (srcinfo, spans) = Srcinfo::parse_with_spans(buf.as_bytes())?;
+    ///
+    /// let span = spans.pkgs[0].get("depends", Some("x86_64"))[0];
+    /// assert_eq!(span.line, 7);
+    /// assert_eq!(span.column, 19);
+    /// assert_eq!(&buf[span.range()], "foo");
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn parse_with_spans<T: BufRead>(b: T) -> Result<(Srcinfo, Spans), Error> {
+        ParseOptions::default().parse_with_spans(b)
     }
 
     /// Builds a complete version string in the format: "epoch-pkgver-pkgrel".
@@ -178,10 +290,50 @@ impl Srcinfo {
     /// # }
     /// ```
     pub fn version(&self) -> String {
-        match self.epoch() {
-            Some(epoch) => format!("{}:{}-{}", epoch, self.pkgver(), self.pkgrel()),
-            None => format!("{}-{}", self.pkgver(), self.pkgrel()),
+        full_version(self.epoch(), self.pkgver(), self.pkgrel())
+    }
+
+    /// Reads the tool that generated this .SRCINFO from the header comment.
+    ///
+    /// ```
+    /// # use srcinfo::Error;
+    /// use srcinfo::{Generator, Srcinfo};
+    ///
+    /// # fn test() -> Result<(), Error> {
+    /// let srcinfo: Srcinfo = "
+    /// # Generated by makepkg 6.0.2
+    /// # Sat Jun 29 10:02:21 UTC 2024
+    /// pkgbase = example
+    /// pkgver = 1.5.0
+    /// pkgrel = 5
+    ///
+    /// pkgname = example".parse()?;
+    ///
+    /// assert_eq!(srcinfo.generator(), Some(Generator::new("makepkg", "6.0.2")));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn generator(&self) -> Option<Generator> {
+        self.comment().lines().find_map(Generator::from_comment)
+    }
+
+    /// Sets the tool that generated this .SRCINFO.
+    ///
+    /// Replaces the existing "Generated by" line of the header comment or adds one to the
+    /// start of it. The header is written when the Srcinfo is formatted.
+    pub fn set_generator(&mut self, generator: Generator) {
+        let line = generator.to_string();
+        let mut lines = self.comment.lines().map(str::to_string).collect::<Vec<_>>();
+
+        match lines
+            .iter_mut()
+            .find(|l| Generator::from_comment(l).is_some())
+        {
+            Some(l) => *l = line,
+            None => lines.insert(0, line),
         }
+
+        self.comment = lines.join("\n");
     }
 
     /// Returns an Iterator over all the pkgnames the Package contains.
@@ -257,6 +409,7 @@ impl Srcinfo {
     get!(source, base.source, &ArchVecs);
     get!(valid_pgp_keys, base.valid_pgp_keys, &[String]);
     get!(no_extract, base.no_extract, &[String]);
+    get!(cksums, base.cksums, &ArchVecs);
     get!(md5sums, base.md5sums, &ArchVecs);
     get!(sha1sums, base.sha1sums, &ArchVecs);
     get!(sha224sums, base.sha224sums, &ArchVecs);
@@ -266,6 +419,7 @@ impl Srcinfo {
     get!(b2sums, base.b2sums, &ArchVecs);
     get!(makedepends, base.makedepends, &ArchVecs);
     get!(checkdepends, base.checkdepends, &ArchVecs);
+    get!(extra, base.extra, &BTreeMap<String, ArchVecs>);
 
     geto!(pkgdesc, pkg.pkgdesc, Option<&str>);
     get!(arch, pkg.arch, &[String]);
@@ -721,6 +875,167 @@ mod tests {
         }
     }
 
+    #[test]
+    fn diagnostics() {
+        let (srcinfo, errors) =
+            Srcinfo::parse_with_diagnostics(include_str!("../tests/srcinfo/errors").as_bytes());
+
+        let errors = errors
+            .iter()
+            .map(|e| (e.line.as_ref().map(|l| l.number), e.kind.to_string()))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            errors,
+            vec![
+                (
+                    Some(3),
+                    "undeclared architecture 'i686' in key 'depends_i686'".to_string()
+                ),
+                (Some(4), "field has no key".to_string()),
+                (Some(5), "key 'pkgdesc' requires a value".to_string()),
+                (Some(8), "key 'noextract' used after pkgname".to_string()),
+                (None, "field 'pkgver' is required".to_string()),
+                (None, "field 'pkgrel' is required".to_string()),
+            ]
+        );
+
+        assert_eq!(srcinfo.pkgbase(), "foo");
+        assert_eq!(srcinfo.pkgnames().collect::<Vec<_>>(), vec!["foo"]);
+        assert_eq!(
+            srcinfo.pkgs[0].depends().arch("x86_64").collect::<Vec<_>>(),
+            vec!["bar"]
+        );
+    }
+
+    #[test]
+    fn diagnostics_options() {
+        let input = "
+pkgbase = foo
+pkgver = 1
+pkgrel = 1
+arch = x86_64
+pkgver = 2
+foo = bar
+
+pkgname = foo
+depends_i686 = bar";
+
+        let mut options = ParseOptions::new();
+        options.strict = true;
+        let (_, errors) = options.parse_with_diagnostics(input.as_bytes());
+        let errors = errors.iter().map(|e| &e.kind).collect::<Vec<_>>();
+        assert_eq!(errors.len(), 3);
+        assert!(matches!(errors[0], ErrorKind::DuplicateField(k) if k == "pkgver"));
+        assert!(matches!(errors[1], ErrorKind::UnknownKey(k) if k == "foo"));
+        assert!(matches!(errors[2], ErrorKind::UndeclaredArch(_, a) if a == "i686"));
+
+        let mut options = ParseOptions::new();
+        options.lenient = true;
+        let (srcinfo, errors) = options.parse_with_diagnostics(input.as_bytes());
+        assert!(errors.is_empty());
+        assert_eq!(srcinfo.pkgver(), "2");
+    }
+
+    #[test]
+    fn diagnostics_good() {
+        let path = fs::read_dir("tests/srcinfo/good").unwrap();
+
+        for file in path.map(|x| x.unwrap()) {
+            let buf = fs::read(file.path()).unwrap();
+            let (srcinfo, errors) = Srcinfo::parse_with_diagnostics(buf.as_slice());
+            assert!(errors.is_empty(), "{:?} {:?}", file, errors);
+            assert_eq!(srcinfo, Srcinfo::from_path(file.path()).unwrap());
+        }
+    }
+
+    #[test]
+    fn spans() {
+        let input = include_str!("../tests/srcinfo/gdc-bin").replace('\n', "\r\n");
+        let (srcinfo, spans) = Srcinfo::parse_with_spans(input.as_bytes()).unwrap();
+
+        assert_eq!(srcinfo, input.parse().unwrap());
+        assert_eq!(spans.pkgs.len(), srcinfo.pkgs.len());
+
+        let span = spans.base.header;
+        assert_eq!((span.line, span.column), (1, 11));
+        assert_eq!(&input[span.range()], "gdc-bin");
+
+        let span = spans.base.get("md5sums", Some("x86_64"));
+        assert_eq!(span.len(), 1);
+        assert_eq!((span[0].line, span[0].column), (11, 19));
+        assert_eq!(&input[span[0].range()], "16d3067ebb3938dba46429a4d9f6178f");
+
+        let span = spans.pkgs[1].header;
+        assert_eq!(span.line, 22);
+        assert_eq!(&input[span.range()], "gdc-gcc");
+
+        let span = spans.pkgs[1].get("provides", None);
+        assert_eq!(span.len(), 2);
+        assert_eq!(&input[span[1].range()], "gcc-libs=6.3.0");
+        assert!(spans.pkgs[1].get("depends", None).is_empty());
+    }
+
+    #[test]
+    fn spans_options() {
+        let input = "pkgbase = foo
+pkgver = 1
+pkgrel = 1
+arch = x86_64
+
+pkgname = foo
+depends_i686 = bar
+pkgdesc = d
+makedepends = cmake";
+
+        let mut options = ParseOptions::new();
+        options.lenient = true;
+        let (srcinfo, spans) = options.parse_with_spans(input.as_bytes()).unwrap();
+        assert_eq!(srcinfo.pkgs[0].pkgdesc(), Some("d"));
+        assert_eq!(spans.pkgs[0].get("pkgdesc", None)[0].line, 8);
+        assert_eq!(
+            srcinfo.makedepends().all().collect::<Vec<_>>(),
+            vec!["cmake"]
+        );
+        assert_eq!(spans.base.get("makedepends", None)[0].line, 9);
+        assert!(spans.pkgs[0].get("makedepends", None).is_empty());
+
+        options.strict = true;
+        let err = options
+            .parse_with_spans(format!("{}\nfoo = bar", input).as_bytes())
+            .unwrap_err();
+        assert!(matches!(err.kind, ErrorKind::UnknownKey(_)));
+    }
+
+    #[test]
+    fn spans_good() {
+        let path = fs::read_dir("tests/srcinfo/good").unwrap();
+
+        for file in path.map(|x| x.unwrap()) {
+            let input = fs::read_to_string(file.path()).unwrap();
+            let lines = input.lines().collect::<Vec<_>>();
+            let (_, spans) = Srcinfo::parse_with_spans(input.as_bytes()).unwrap();
+
+            for section in std::iter::once(&spans.base).chain(&spans.pkgs) {
+                let span = section.header;
+                assert!(lines[span.line - 1].ends_with(&input[span.range()]));
+
+                for (key, arch, spans) in section.iter() {
+                    let key = match arch {
+                        Some(arch) => format!("{}_{}", key, arch),
+                        None => key.to_string(),
+                    };
+
+                    for span in spans {
+                        let line = lines[span.line - 1].trim();
+                        let value = &input[span.range()];
+                        assert_eq!(line, format!("{} = {}", key, value), "{:?}", file);
+                    }
+                }
+            }
+        }
+    }
+
     #[test]
     fn error_empty_key() {
         let err = include_str!("../tests/srcinfo/no_key")
@@ -755,6 +1070,46 @@ mod tests {
             ErrorKind::EmptyValue(ref key) => assert_eq!(key, "arch"),
             _ => panic!("{:?}", err),
         }
+
+        let err = "pkgbase = foo\npkgver = 1\npkgrel = 1\npkgname ="
+            .parse::<Srcinfo>()
+            .unwrap_err();
+        assert_eq!(err.line.as_ref().unwrap().number, 4);
+
+        match err.kind {
+            ErrorKind::EmptyValue(ref key) => assert_eq!(key, "pkgname"),
+            _ => panic!("{:?}", err),
+        }
+    }
+
+    #[test]
+    fn error_empty_pkgname_before_pkgbase() {
+        let err = "pkgname =\npkgbase = foo".parse::<Srcinfo>().unwrap_err();
+        assert_eq!(err.line.as_ref().unwrap().number, 1);
+
+        match err.kind {
+            ErrorKind::KeyBeforePkgbase(ref key) => assert_eq!(key, "pkgname"),
+            _ => panic!("{:?}", err),
+        }
+    }
+
+    #[test]
+    fn error_empty_duplicate_pkgbase() {
+        let err = "pkgbase = foo\npkgver = 1\npkgbase ="
+            .parse::<Srcinfo>()
+            .unwrap_err();
+        assert_eq!(err.line.as_ref().unwrap().number, 3);
+
+        match err.kind {
+            ErrorKind::DuplicatePkgbase => {}
+            _ => panic!("{:?}", err),
+        }
+
+        let err = "pkgbase =".parse::<Srcinfo>().unwrap_err();
+        match err.kind {
+            ErrorKind::EmptyValue(ref key) => assert_eq!(key, "pkgbase"),
+            _ => panic!("{:?}", err),
+        }
     }
 
     #[test]
@@ -768,6 +1123,39 @@ mod tests {
         }
     }
 
+    #[test]
+    fn error_invalid_utf8() {
+        let input = include_bytes!("../tests/srcinfo/latin1");
+        let err = Srcinfo::from_bytes(input).unwrap_err();
+        let line = err.line.as_ref().unwrap();
+        assert_eq!(line.number, 8);
+        assert_eq!(line.line, "pkgdesc = Caf\u{fffd} client");
+
+        match err.kind {
+            ErrorKind::InvalidUtf8(offset) => assert_eq!(input[offset], 0xe9),
+            _ => panic!("{:?}", err),
+        }
+
+        let err = Srcinfo::from_path("tests/srcinfo/latin1").unwrap_err();
+        assert_eq!(err.line.unwrap().number, 8);
+        let err = Srcinfo::from_buf(&input[..]).unwrap_err();
+        assert!(matches!(err.kind, ErrorKind::InvalidUtf8(_)));
+    }
+
+    #[test]
+    fn lossy_utf8() {
+        let mut options = ParseOptions::new();
+        options.lossy_utf8 = true;
+
+        let srcinfo = options
+            .parse_bytes(include_bytes!("../tests/srcinfo/latin1"))
+            .unwrap();
+        assert_eq!(srcinfo.pkgs()[0].pkgdesc(), Some("Caf\u{fffd} client"));
+
+        let srcinfo = options.parse_path("tests/srcinfo/latin1").unwrap();
+        assert_eq!(srcinfo.pkgs()[0].pkgdesc(), Some("Caf\u{fffd} client"));
+    }
+
     #[test]
     fn arch_in_package() {
         let srcinfo = include_str!("../tests/srcinfo/arch_in_package")
@@ -793,6 +1181,195 @@ mod tests {
         }
     }
 
+    #[test]
+    fn unknown_keys() {
+        let srcinfo = include_str!("../tests/srcinfo/unknown_keys")
+            .parse::<Srcinfo>()
+            .unwrap();
+
+        let extra = srcinfo.extra().iter().collect::<Vec<_>>();
+        assert_eq!(
+            extra,
+            vec![
+                (
+                    &"foo".to_string(),
+                    &ArchVecs::from(vec![
+                        ArchVec::with_values(
+                            None::<String>,
+                            vec!["a".to_string(), "b".to_string()]
+                        ),
+                        ArchVec::with_values(Some("x86_64"), vec!["c".to_string()]),
+                    ])
+                ),
+                (
+                    &"xdata".to_string(),
+                    &ArchVecs::from(vec![ArchVec::with_values(
+                        None::<String>,
+                        vec!["pkgtype=debug".to_string()]
+                    )])
+                ),
+            ]
+        );
+
+        assert!(srcinfo.pkgs[0].extra().is_empty());
+        let extra = srcinfo.pkgs[1].extra().get("bar").unwrap();
+        assert_eq!(extra.all().collect::<Vec<_>>(), vec!["1"]);
+    }
+
+    #[test]
+    fn cksums() {
+        let srcinfo = include_str!("../tests/srcinfo/cksums")
+            .parse::<Srcinfo>()
+            .unwrap();
+
+        assert!(srcinfo.extra().is_empty());
+        assert_eq!(
+            srcinfo.cksums().arch("x86_64").collect::<Vec<_>>(),
+            vec!["1392746235", "3920483752"]
+        );
+        assert_eq!(
+            srcinfo.cksums().arch("aarch64").collect::<Vec<_>>(),
+            vec!["1392746235", "SKIP"]
+        );
+    }
+
+    #[test]
+    fn lenient() {
+        let mut lenient = ParseOptions::new();
+        lenient.lenient = true;
+
+        let path = fs::read_dir("tests/srcinfo/good").unwrap();
+        for file in path.map(|x| x.unwrap()) {
+            let input = fs::read_to_string(file.path()).unwrap();
+            let (srcinfo, warnings) = lenient.parse_str_with_warnings(&input).unwrap();
+            assert!(warnings.is_empty(), "{:?} {:?}", file, warnings);
+            assert_eq!(srcinfo, input.parse().unwrap());
+        }
+
+        let input = include_str!("../tests/srcinfo/lenient");
+        assert!(input.parse::<Srcinfo>().is_err());
+        let (srcinfo, warnings) = lenient.parse_str_with_warnings(input).unwrap();
+
+        assert_eq!(srcinfo.pkgdesc(), None);
+        assert_eq!(srcinfo.pkgrel(), "2");
+        assert_eq!(
+            srcinfo.makedepends().arch("x86_64").collect::<Vec<_>>(),
+            vec!["baz"]
+        );
+        assert!(srcinfo.source().is_empty());
+        assert!(srcinfo.pkgs[0].depends().is_empty());
+        assert_eq!(srcinfo.to_string().parse::<Srcinfo>().unwrap(), srcinfo);
+
+        let warnings = warnings
+            .iter()
+            .map(|w| (w.line.as_ref().unwrap().number, w.kind.to_string()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            warnings,
+            vec![
+                (4, "key 'pkgdesc' requires a value".to_string()),
+                (
+                    6,
+                    "undeclared architecture 'i686' in key 'source_i686'".to_string()
+                ),
+                (
+                    9,
+                    "undeclared architecture 'aarch64' in key 'depends_aarch64'".to_string()
+                ),
+                (10, "key 'makedepends' used after pkgname".to_string()),
+                (11, "key 'pkgrel' used after pkgname".to_string()),
+            ]
+        );
+
+        let (_, warnings) = ParseOptions::new()
+            .parse_str_with_warnings(include_str!("../tests/srcinfo/libc++"))
+            .unwrap();
+        assert!(warnings.is_empty());
+
+        let err = lenient
+            .parse_str(include_str!("../tests/srcinfo/no_key"))
+            .unwrap_err();
+        assert!(matches!(err.kind, ErrorKind::EmptyKey));
+    }
+
+    #[test]
+    fn strict() {
+        let mut strict = ParseOptions::new();
+        strict.strict = true;
+
+        let path = fs::read_dir("tests/srcinfo/good").unwrap();
+        for file in path.map(|x| x.unwrap()) {
+            let srcinfo = strict.parse_path(file.path());
+            assert!(srcinfo.is_ok(), "{:?} {:?}", file, srcinfo);
+        }
+
+        let err = strict
+            .parse_str(include_str!("../tests/srcinfo/unknown_keys"))
+            .unwrap_err();
+        assert_eq!(err.line.as_ref().unwrap().number, 5);
+        match err.kind {
+            ErrorKind::UnknownKey(ref key) => assert_eq!(key, "foo"),
+            _ => panic!("{:?}", err),
+        }
+
+        let input = include_str!("../tests/srcinfo/duplicate_field");
+        assert!(input.parse::<Srcinfo>().is_ok());
+        let err = strict.parse_str(input).unwrap_err();
+        assert_eq!(err.line.as_ref().unwrap().number, 11);
+        match err.kind {
+            ErrorKind::DuplicateField(ref key) => assert_eq!(key, "pkgdesc"),
+            _ => panic!("{:?}", err),
+        }
+
+        let input = include_str!("../tests/srcinfo/duplicate_pkgname");
+        assert!(input.parse::<Srcinfo>().is_ok());
+        let err = strict.parse_str(input).unwrap_err();
+        assert_eq!(err.line.as_ref().unwrap().number, 9);
+        match err.kind {
+            ErrorKind::DuplicatePkgname(ref name) => assert_eq!(name, "bar"),
+            _ => panic!("{:?}", err),
+        }
+
+        let input = "pkgbase = foo\npkgver = 1\npkgver = 2";
+        let err = strict.parse_str(input).unwrap_err();
+        assert_eq!(err.line.as_ref().unwrap().number, 3);
+        match err.kind {
+            ErrorKind::DuplicateField(ref key) => assert_eq!(key, "pkgver"),
+            _ => panic!("{:?}", err),
+        }
+    }
+
+    #[test]
+    fn generator() {
+        for file in fs::read_dir("tests/srcinfo/good").unwrap() {
+            let path = file.unwrap().path();
+            let input = fs::read_to_string(&path).unwrap();
+            let srcinfo = input.parse::<Srcinfo>().unwrap();
+
+            // the tool named by the fixture's own header comment, if it has one
+            let expected = input
+                .lines()
+                .take_while(|l| l.starts_with('#'))
+                .find_map(|l| l.split_once("Generated by "))
+                .and_then(|(_, rest)| rest.split_whitespace().next());
+            let tool = srcinfo.generator().map(|g| g.tool);
+            assert_eq!(tool.as_deref(), expected, "{:?}", path);
+            if let Some(tool) = expected {
+                assert!(["mksrcinfo", "makepkg"].contains(&tool), "{:?}", path);
+            }
+        }
+
+        let mut srcinfo = include_str!("../tests/srcinfo/comment-2")
+            .parse::<Srcinfo>()
+            .unwrap();
+        assert_eq!(srcinfo.generator(), None);
+        srcinfo.set_generator(Generator::new("makepkg", "6.0.2"));
+        assert_eq!(srcinfo.comment(), "Generated by makepkg 6.0.2\n123\nabc");
+        srcinfo.set_generator(Generator::new("makepkg", "7.0.0"));
+        assert_eq!(srcinfo.comment(), "Generated by makepkg 7.0.0\n123\nabc");
+        assert_eq!(srcinfo.generator().unwrap().version, "7.0.0");
+    }
+
     #[test]
     fn comment_1() {
         let srcinfo = include_str!("../tests/srcinfo/comment-1")
@@ -810,4 +1387,28 @@ mod tests {
 
         assert_eq!(srcinfo.comment(), "123\nabc");
     }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_round_trip() {
+        let srcinfo = Srcinfo::from_path("tests/srcinfo/libc++").unwrap();
+        let mut json = serde_json::to_value(&srcinfo).unwrap();
+        assert_eq!(
+            serde_json::from_value::<Srcinfo>(json.clone()).unwrap(),
+            srcinfo
+        );
+
+        // data serialized before cksums, extra and provenance existed
+        let base = json["base"].as_object_mut().unwrap();
+        base.remove("cksums");
+        base.remove("extra");
+        let pkgs = (0..srcinfo.pkgs.len()).map(|i| format!("/pkgs/{}", i));
+        for path in pkgs.chain(["/pkg".to_string()]) {
+            let pkg = json.pointer_mut(&path).unwrap().as_object_mut().unwrap();
+            pkg.remove("extra");
+            pkg.remove("provenance");
+        }
+
+        assert_eq!(serde_json::from_value::<Srcinfo>(json).unwrap(), srcinfo);
+    }
 }
@@ -0,0 +1,337 @@
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+
+use crate::archvec::{ArchVecs, ArchVecsRef};
+use crate::error::Error;
+use crate::generator::Generator;
+use crate::options::ParseOptions;
+use crate::provenance::Provenances;
+use crate::srcinfo::{Package, PackageBase, Srcinfo, full_version, get};
+
+// like get! but for the fields that are already borrowed and are returned by value
+macro_rules! copy {
+    ($fn:ident, $( $field:ident ).+, $typ:ty) => {
+        #[allow(missing_docs)]
+        pub fn $fn(&self) -> $typ {
+            self. $( $field ).+
+        }
+    };
+}
+
+fn to_strings(v: &[&str]) -> Vec<String> {
+    v.iter().map(|s| s.to_string()).collect()
+}
+
+fn to_extra(extra: &BTreeMap<&str, ArchVecsRef>) -> BTreeMap<String, ArchVecs> {
+    extra
+        .iter()
+        .map(|(k, v)| (k.to_string(), v.to_owned()))
+        .collect()
+}
+
+/// A borrowed version of [`PackageBase`].
+#[allow(missing_docs)]
+#[derive(Clone, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[non_exhaustive]
+pub struct PackageBaseRef<'a> {
+    pub pkgbase: &'a str,
+    pub pkgver: &'a str,
+    pub pkgrel: &'a str,
+    pub epoch: Option<&'a str>,
+    pub source: ArchVecsRef<'a>,
+    pub valid_pgp_keys: Vec<&'a str>,
+    pub no_extract: Vec<&'a str>,
+    pub cksums: ArchVecsRef<'a>,
+    pub md5sums: ArchVecsRef<'a>,
+    pub sha1sums: ArchVecsRef<'a>,
+    pub sha224sums: ArchVecsRef<'a>,
+    pub sha256sums: ArchVecsRef<'a>,
+    pub sha384sums: ArchVecsRef<'a>,
+    pub sha512sums: ArchVecsRef<'a>,
+    pub b2sums: ArchVecsRef<'a>,
+    pub makedepends: ArchVecsRef<'a>,
+    pub checkdepends: ArchVecsRef<'a>,
+    /// Unknown keys from the pkgbase section
+    pub extra: BTreeMap<&'a str, ArchVecsRef<'a>>,
+}
+
+impl PackageBaseRef<'_> {
+    /// Copies the fields into an owned [`PackageBase`]
+    pub fn to_owned(&self) -> PackageBase {
+        PackageBase {
+            pkgbase: self.pkgbase.to_string(),
+            pkgver: self.pkgver.to_string(),
+            pkgrel: self.pkgrel.to_string(),
+            epoch: self.epoch.map(str::to_string),
+            source: self.source.to_owned(),
+            valid_pgp_keys: to_strings(&self.valid_pgp_keys),
+            no_extract: to_strings(&self.no_extract),
+            cksums: self.cksums.to_owned(),
+            md5sums: self.md5sums.to_owned(),
+            sha1sums: self.sha1sums.to_owned(),
+            sha224sums: self.sha224sums.to_owned(),
+            sha256sums: self.sha256sums.to_owned(),
+            sha384sums: self.sha384sums.to_owned(),
+            sha512sums: self.sha512sums.to_owned(),
+            b2sums: self.b2sums.to_owned(),
+            makedepends: self.makedepends.to_owned(),
+            checkdepends: self.checkdepends.to_owned(),
+            extra: to_extra(&self.extra),
+        }
+    }
+}
+
+/// A borrowed version of [`Package`].
+#[allow(missing_docs)]
+#[derive(Clone, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[non_exhaustive]
+pub struct PackageRef<'a> {
+    pub pkgname: &'a str,
+    pub pkgdesc: Option<&'a str>,
+    pub arch: Vec<&'a str>,
+    pub url: Option<&'a str>,
+    pub license: Vec<&'a str>,
+    pub groups: Vec<&'a str>,
+    pub depends: ArchVecsRef<'a>,
+    pub optdepends: ArchVecsRef<'a>,
+    pub provides: ArchVecsRef<'a>,
+    pub conflicts: ArchVecsRef<'a>,
+    pub replaces: ArchVecsRef<'a>,
+    pub backup: Vec<&'a str>,
+    pub options: Vec<&'a str>,
+    pub install: Option<&'a str>,
+    pub changelog: Option<&'a str>,
+    /// Unknown keys from the pkgname section
+    pub extra: BTreeMap<&'a str, ArchVecsRef<'a>>,
+    // the fields that the package section set or cleared itself, see PackageRef::provenance
+    pub(crate) provenance: Provenances<&'a str>,
+}
+
+impl<'a> PackageRef<'a> {
+    copy!(pkgname, pkgname, &'a str);
+    copy!(pkgdesc, pkgdesc, Option<&'a str>);
+    get!(arch, arch, &[&'a str]);
+    copy!(url, url, Option<&'a str>);
+    get!(license, license, &[&'a str]);
+    get!(groups, groups, &[&'a str]);
+    get!(depends, depends, &ArchVecsRef<'a>);
+    get!(optdepends, optdepends, &ArchVecsRef<'a>);
+    get!(provides, provides, &ArchVecsRef<'a>);
+    get!(conflicts, conflicts, &ArchVecsRef<'a>);
+    get!(replaces, replaces, &ArchVecsRef<'a>);
+    get!(backup, backup, &[&'a str]);
+    get!(options, options, &[&'a str]);
+    copy!(install, install, Option<&'a str>);
+    copy!(changelog, changelog, Option<&'a str>);
+    get!(extra, extra, &BTreeMap<&'a str, ArchVecsRef<'a>>);
+
+    /// Copies the fields into an owned [`Package`]
+    pub fn to_owned(&self) -> Package {
+        Package {
+            pkgname: self.pkgname.to_string(),
+            pkgdesc: self.pkgdesc.map(str::to_string),
+            arch: to_strings(&self.arch),
+            url: self.url.map(str::to_string),
+            license: to_strings(&self.license),
+            groups: to_strings(&self.groups),
+            depends: self.depends.to_owned(),
+            optdepends: self.optdepends.to_owned(),
+            provides: self.provides.to_owned(),
+            conflicts: self.conflicts.to_owned(),
+            replaces: self.replaces.to_owned(),
+            backup: to_strings(&self.backup),
+            options: to_strings(&self.options),
+            install: self.install.map(str::to_string),
+            changelog: self.changelog.map(str::to_string),
+            extra: to_extra(&self.extra),
+            provenance: Provenances(
+                self.provenance
+                    .iter()
+                    .map(|&(k, a, p)| (k.to_string(), a.map(str::to_string), p))
+                    .collect(),
+            ),
+        }
+    }
+}
+
+/// A borrowed version of [`Srcinfo`].
+///
+/// SrcinfoRef borrows every key and value from the input instead of copying them into a
+/// String. This makes parsing a large amount of .SRCINFO files much cheaper when the input
+/// outlives the result. [`SrcinfoRef::to_owned`] converts it into a [`Srcinfo`].
+///
+/// ```
+/// # use srcinfo::Error;
+/// use srcinfo::SrcinfoRef;
+///
+/// # fn test() -> Result<(), Error> {
+/// let input = "
+/// pkgbase = example
+/// pkgver = 1.5.0
+/// pkgrel = 5
+///
+/// pkgname = example";
+///
+/// let srcinfo = SrcinfoRef::parse(input)?;
+/// assert_eq!(srcinfo.pkgbase(), "example");
+///
+/// let srcinfo = srcinfo.to_owned();
+/// assert_eq!(srcinfo.version(), "1.5.0-5");
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[non_exhaustive]
+pub struct SrcinfoRef<'a> {
+    /// The header comment
+    ///
+    /// Only allocates if the header is more than one line.
+    pub comment: Cow<'a, str>,
+    /// Fields belonging to the pkgbase
+    pub base: PackageBaseRef<'a>,
+    /// Fields belonging to the pkgbase, may be overridden inside of each package
+    pub pkg: PackageRef<'a>,
+    /// The packages this .SRCINFO contains
+    pub pkgs: Vec<PackageRef<'a>>,
+}
+
+impl<'a> SrcinfoRef<'a> {
+    /// Parse a string, borrowing from it.
+    pub fn parse(s: &'a str) -> Result<SrcinfoRef<'a>, Error> {
+        ParseOptions::default().parse_ref(s)
+    }
+
+    /// Copies the fields into an owned [`Srcinfo`]
+    pub fn to_owned(&self) -> Srcinfo {
+        Srcinfo {
+            comment: self.comment.to_string(),
+            base: self.base.to_owned(),
+            pkg: self.pkg.to_owned(),
+            pkgs: self.pkgs.iter().map(PackageRef::to_owned).collect(),
+        }
+    }
+
+    /// Builds a complete version string in the format: "epoch-pkgver-pkgrel".
+    ///
+    /// If the epoch is none then the epoch and connecting hyphen will be omitted.
+    pub fn version(&self) -> String {
+        full_version(self.epoch(), self.pkgver(), self.pkgrel())
+    }
+
+    /// Reads the tool that generated this .SRCINFO from the header comment.
+    pub fn generator(&self) -> Option<Generator> {
+        self.comment().lines().find_map(Generator::from_comment)
+    }
+
+    /// Returns an Iterator over all the pkgnames the Package contains.
+    pub fn pkgnames(&self) -> impl Iterator<Item = &'a str> {
+        self.pkgs().iter().map(|p| p.pkgname)
+    }
+
+    /// Searches for a package with a given pkgname
+    pub fn pkg<S: AsRef<str>>(&self, name: S) -> Option<&PackageRef<'a>> {
+        self.pkgs().iter().find(|p| p.pkgname == name.as_ref())
+    }
+
+    /// Returns the list of packages in this SrcinfoRef
+    pub fn pkgs(&self) -> &[PackageRef<'a>] {
+        &self.pkgs
+    }
+
+    get!(comment, comment, &str);
+    copy!(pkgbase, base.pkgbase, &'a str);
+    copy!(pkgver, base.pkgver, &'a str);
+    copy!(pkgrel, base.pkgrel, &'a str);
+    copy!(epoch, base.epoch, Option<&'a str>);
+    get!(source, base.source, &ArchVecsRef<'a>);
+    get!(valid_pgp_keys, base.valid_pgp_keys, &[&'a str]);
+    get!(no_extract, base.no_extract, &[&'a str]);
+    get!(cksums, base.cksums, &ArchVecsRef<'a>);
+    get!(md5sums, base.md5sums, &ArchVecsRef<'a>);
+    get!(sha1sums, base.sha1sums, &ArchVecsRef<'a>);
+    get!(sha224sums, base.sha224sums, &ArchVecsRef<'a>);
+    get!(sha256sums, base.sha256sums, &ArchVecsRef<'a>);
+    get!(sha384sums, base.sha384sums, &ArchVecsRef<'a>);
+    get!(sha512sums, base.sha512sums, &ArchVecsRef<'a>);
+    get!(b2sums, base.b2sums, &ArchVecsRef<'a>);
+    get!(makedepends, base.makedepends, &ArchVecsRef<'a>);
+    get!(checkdepends, base.checkdepends, &ArchVecsRef<'a>);
+    get!(extra, base.extra, &BTreeMap<&'a str, ArchVecsRef<'a>>);
+
+    copy!(pkgdesc, pkg.pkgdesc, Option<&'a str>);
+    get!(arch, pkg.arch, &[&'a str]);
+    copy!(url, pkg.url, Option<&'a str>);
+    get!(license, pkg.license, &[&'a str]);
+    get!(groups, pkg.groups, &[&'a str]);
+    get!(depends, pkg.depends, &ArchVecsRef<'a>);
+    get!(optdepends, pkg.optdepends, &ArchVecsRef<'a>);
+    get!(provides, pkg.provides, &ArchVecsRef<'a>);
+    get!(conflicts, pkg.conflicts, &ArchVecsRef<'a>);
+    get!(replaces, pkg.replaces, &ArchVecsRef<'a>);
+    get!(backup, pkg.backup, &[&'a str]);
+    get!(options, pkg.options, &[&'a str]);
+    copy!(install, pkg.install, Option<&'a str>);
+    copy!(changelog, pkg.changelog, Option<&'a str>);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn borrows(input: &str, s: &str) -> bool {
+        let range = input.as_bytes().as_ptr_range();
+        range.contains(&s.as_ptr())
+    }
+
+    #[test]
+    fn test_borrowed() {
+        let input = include_str!("../tests/srcinfo/libc++");
+        let srcinfo = SrcinfoRef::parse(input).unwrap();
+
+        assert!(srcinfo.comment().is_empty());
+        assert!(borrows(input, srcinfo.pkgbase()));
+        assert!(borrows(input, srcinfo.pkgver()));
+        assert!(srcinfo.source().all().all(|s| borrows(input, s)));
+
+        let pkg = srcinfo.pkg("libc++").unwrap();
+        assert!(borrows(input, pkg.pkgname()));
+        assert_eq!(
+            pkg.depends().arch("x86_64").collect::<Vec<_>>(),
+            vec!["libc++abi=6.0.0-1"]
+        );
+        assert_eq!(pkg.arch(), &["i686", "x86_64"]);
+        assert_eq!(srcinfo.version(), "6.0.0-1");
+        assert_eq!(
+            srcinfo.pkgnames().collect::<Vec<_>>(),
+            vec!["libc++", "libc++abi", "libc++experimental"]
+        );
+    }
+
+    #[test]
+    fn test_comment() {
+        let input = include_str!("../tests/srcinfo/comment-2");
+        let srcinfo = SrcinfoRef::parse(input).unwrap();
+        assert_eq!(srcinfo.comment(), "123\nabc");
+
+        let input = "# foo\npkgbase = a\npkgver = 1\npkgrel = 1\npkgname = a";
+        let srcinfo = SrcinfoRef::parse(input).unwrap();
+        assert!(matches!(srcinfo.comment, Cow::Borrowed("foo")));
+    }
+
+    #[test]
+    fn test_to_owned() {
+        for file in fs::read_dir("tests/srcinfo/good").unwrap() {
+            let input = fs::read_to_string(file.unwrap().path()).unwrap();
+            let srcinfo = SrcinfoRef::parse(&input).unwrap();
+            let owned = srcinfo.to_owned();
+
+            assert_eq!(srcinfo.pkgbase(), owned.pkgbase());
+            assert_eq!(srcinfo.pkgs().len(), owned.pkgs().len());
+            assert_eq!(owned.to_string().parse::<Srcinfo>().unwrap(), owned);
+        }
+    }
+}
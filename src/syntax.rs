@@ -0,0 +1,504 @@
+use std::fmt::{Display, Formatter, Result as FmtResult};
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::ops::Range;
+use std::path::Path;
+
+use crate::error::Error;
+use crate::options::ParseOptions;
+use crate::srcinfo::Srcinfo;
+
+/// The terminator at the end of a [`Line`].
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum LineEnding {
+    /// The line is the last line and has no terminator
+    #[default]
+    None,
+    /// "\n"
+    Lf,
+    /// "\r\n"
+    CrLf,
+}
+
+impl LineEnding {
+    /// The terminator as a string
+    pub fn as_str(self) -> &'static str {
+        match self {
+            LineEnding::None => "",
+            LineEnding::Lf => "\n",
+            LineEnding::CrLf => "\r\n",
+        }
+    }
+}
+
+/// The tokens of a single [`Line`].
+///
+/// This is the only tokenizer in the crate, it is used by [`SyntaxTree`] as well as when
+/// parsing a [`Srcinfo`] or reading [`Event`](crate::Event)s.
+///
+/// All slices borrow from the line. Keys and values have their surrounding whitespace removed.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum LineKind<'a> {
+    /// An empty or whitespace only line
+    Blank,
+    /// A comment, holds the text after the '#'
+    Comment(&'a str),
+    /// A "key = value" pair, the value may be empty
+    Field {
+        /// The key including any architecture suffix
+        key: &'a str,
+        /// The value
+        value: &'a str,
+    },
+    /// A line that is not a comment and does not contain '='
+    Invalid(&'a str),
+}
+
+impl<'a> LineKind<'a> {
+    /// Tokenizes a line of text.
+    pub fn new(line: &'a str) -> LineKind<'a> {
+        let line = line.trim();
+
+        if line.is_empty() {
+            LineKind::Blank
+        } else if let Some(comment) = line.strip_prefix('#') {
+            LineKind::Comment(comment)
+        } else if let Some((key, value)) = line.split_once('=') {
+            LineKind::Field {
+                key: key.trim(),
+                value: value.trim(),
+            }
+        } else {
+            LineKind::Invalid(line)
+        }
+    }
+}
+
+/// A single line of a .SRCINFO, stored exactly as it appeared in the input.
+#[derive(Clone, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Line {
+    text: String,
+    ending: LineEnding,
+}
+
+impl Line {
+    /// Creates a line from its text. The text should not contain a line terminator.
+    pub fn new<S: Into<String>>(text: S) -> Line {
+        Line {
+            text: text.into(),
+            ending: LineEnding::Lf,
+        }
+    }
+
+    /// Creates a "key = value" line.
+    pub fn field(key: &str, value: &str) -> Line {
+        Line::new(format!("{} = {}", key, value))
+    }
+
+    /// The text of the line without the line terminator
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// The line terminator
+    pub fn ending(&self) -> LineEnding {
+        self.ending
+    }
+
+    /// Sets the line terminator
+    pub fn set_ending(&mut self, ending: LineEnding) {
+        self.ending = ending;
+    }
+
+    /// The leading whitespace of the line
+    pub fn indent(&self) -> &str {
+        let len = self.text.len() - self.text.trim_start().len();
+        &self.text[..len]
+    }
+
+    /// Tokenizes the line
+    pub fn kind(&self) -> LineKind<'_> {
+        LineKind::new(&self.text)
+    }
+
+    /// The key of the line if the line is a field
+    pub fn key(&self) -> Option<&str> {
+        match self.kind() {
+            LineKind::Field { key, .. } => Some(key),
+            _ => None,
+        }
+    }
+
+    /// The value of the line if the line is a field
+    pub fn value(&self) -> Option<&str> {
+        match self.kind() {
+            LineKind::Field { value, .. } => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Replaces the value of a field, keeping the indentation and spacing of the line.
+    ///
+    /// Returns false and leaves the line untouched if the line is not a field.
+    pub fn set_value(&mut self, value: &str) -> bool {
+        if self.key().is_none() {
+            return false;
+        }
+
+        let eq = self.text.find('=').unwrap() + 1;
+        let rest = &self.text[eq..];
+        let end = eq + rest.trim_end().len();
+        let start = (eq + rest.len() - rest.trim_start().len()).min(end);
+
+        let space = if start == eq && !value.is_empty() {
+            " "
+        } else {
+            ""
+        };
+        self.text
+            .replace_range(start..end, &format!("{}{}", space, value));
+        true
+    }
+}
+
+impl Display for Line {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        write!(f, "{}{}", self.text, self.ending.as_str())
+    }
+}
+
+/// A lossless syntax tree of a .SRCINFO file.
+///
+/// The tree keeps every line of the input including comments, blank lines, indentation and
+/// line terminators. Printing the tree gives back the exact input it was created from.
+///
+/// This allows a .SRCINFO to be edited without rewriting the rest of the file, which
+/// [`Srcinfo`]'s Display implementation can not do.
+///
+/// ```
+/// # use srcinfo::Error;
+/// use srcinfo::SyntaxTree;
+///
+/// # fn test() -> Result<(), Error> {
+/// let input = "# comment
+/// pkgbase = example
+///     pkgver = 1.5.0
+///     pkgrel = 5
+///
+/// pkgname = example
+/// ";
+///
+/// let mut tree = SyntaxTree::from(input);
+/// assert_eq!(tree.to_string(), input);
+///
+/// tree.field_mut(None, "pkgrel").unwrap().set_value("6");
+/// assert_eq!(tree.to_srcinfo()?.pkgrel(), "6");
+/// assert_eq!(tree.to_string(), input.replace("pkgrel = 5", "pkgrel = 6"));
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct SyntaxTree {
+    lines: Vec<Line>,
+}
+
+impl From<&str> for SyntaxTree {
+    fn from(s: &str) -> Self {
+        let lines = s.split_inclusive('\n').map(split_ending).collect();
+        SyntaxTree { lines }
+    }
+}
+
+impl Display for SyntaxTree {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        for line in &self.lines {
+            line.fmt(f)?;
+        }
+        Ok(())
+    }
+}
+
+fn split_ending(line: &str) -> Line {
+    let (text, ending) = if let Some(text) = line.strip_suffix("\r\n") {
+        (text, LineEnding::CrLf)
+    } else if let Some(text) = line.strip_suffix('\n') {
+        (text, LineEnding::Lf)
+    } else {
+        (line, LineEnding::None)
+    };
+
+    Line {
+        text: text.to_string(),
+        ending,
+    }
+}
+
+impl SyntaxTree {
+    /// Creates a new empty SyntaxTree
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Reads a SyntaxTree from a BufRead.
+    pub fn from_buf<T: BufRead>(mut b: T) -> Result<SyntaxTree, Error> {
+        let mut lines = Vec::new();
+        let mut line = String::new();
+
+        while b.read_line(&mut line)? != 0 {
+            lines.push(split_ending(&line));
+            line.clear();
+        }
+
+        Ok(SyntaxTree { lines })
+    }
+
+    /// Reads a SyntaxTree from the file at a given path.
+    pub fn from_path<P: AsRef<Path>>(s: P) -> Result<SyntaxTree, Error> {
+        let file = File::open(s)?;
+        SyntaxTree::from_buf(BufReader::new(file))
+    }
+
+    /// Parses the tree into a [`Srcinfo`].
+    ///
+    /// The parser tokenizes lines with [`LineKind`], the same as [`Line::kind`], so the
+    /// Srcinfo always matches the keys and values the tree holds.
+    pub fn to_srcinfo(&self) -> Result<Srcinfo, Error> {
+        ParseOptions::default().parse_tree(self)
+    }
+
+    /// The lines of the tree
+    pub fn lines(&self) -> &[Line] {
+        &self.lines
+    }
+
+    /// The lines of the tree, lines may be freely added, removed or modified
+    pub fn lines_mut(&mut self) -> &mut Vec<Line> {
+        &mut self.lines
+    }
+
+    /// Finds the range of lines belonging to a section.
+    ///
+    /// A pkgname of None selects the pkgbase section. The range starts at the pkgbase or
+    /// pkgname line and ends before the next pkgname line.
+    pub fn section(&self, pkgname: Option<&str>) -> Option<Range<usize>> {
+        let start = self.lines.iter().position(|line| match pkgname {
+            Some(name) => {
+                line.kind()
+                    == LineKind::Field {
+                        key: "pkgname",
+                        value: name,
+                    }
+            }
+            None => line.key() == Some("pkgbase"),
+        })?;
+
+        let end = self.lines[start + 1..]
+            .iter()
+            .position(|line| line.key() == Some("pkgname"))
+            .map_or(self.lines.len(), |end| start + 1 + end);
+
+        Some(start..end)
+    }
+
+    /// Finds the first line in a section with the given key.
+    ///
+    /// The key includes the architecture suffix, if any.
+    pub fn field(&self, pkgname: Option<&str>, key: &str) -> Option<&Line> {
+        let range = self.section(pkgname)?;
+        self.lines[range]
+            .iter()
+            .find(|line| line.key() == Some(key))
+    }
+
+    /// Finds the first line in a section with the given key.
+    ///
+    /// The key includes the architecture suffix, if any.
+    pub fn field_mut(&mut self, pkgname: Option<&str>, key: &str) -> Option<&mut Line> {
+        let range = self.section(pkgname)?;
+        self.lines[range]
+            .iter_mut()
+            .find(|line| line.key() == Some(key))
+    }
+
+    /// Appends a "key = value" line to the end of a section.
+    ///
+    /// The new line copies the indentation of the last field in the section.
+    /// Returns false if the section does not exist.
+    pub fn insert_field(&mut self, pkgname: Option<&str>, key: &str, value: &str) -> bool {
+        let Some(range) = self.section(pkgname) else {
+            return false;
+        };
+
+        let Some(last) = self.lines[range.clone()]
+            .iter()
+            .rposition(|line| line.key().is_some())
+            .map(|i| range.start + i)
+        else {
+            return false;
+        };
+
+        let indent = if last == range.start {
+            "\t"
+        } else {
+            self.lines[last].indent()
+        };
+        let mut line = Line::new(format!("{}{} = {}", indent, key, value));
+        line.ending = self.lines[last].ending;
+        if line.ending == LineEnding::None {
+            self.lines[last].ending = LineEnding::Lf;
+        }
+
+        self.lines.insert(last + 1, line);
+        true
+    }
+
+    /// Removes every line in a section with the given key.
+    ///
+    /// Returns the number of lines removed.
+    pub fn remove_field(&mut self, pkgname: Option<&str>, key: &str) -> usize {
+        let Some(range) = self.section(pkgname) else {
+            return 0;
+        };
+
+        let mut i = range.start + 1;
+        let mut end = range.end;
+        let mut removed = 0;
+
+        while i < end {
+            if self.lines[i].key() == Some(key) {
+                self.lines.remove(i);
+                end -= 1;
+                removed += 1;
+            } else {
+                i += 1;
+            }
+        }
+
+        removed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::{read_dir, read_to_string};
+
+    #[test]
+    fn test_lossless() {
+        for file in read_dir("tests/srcinfo/good/").unwrap() {
+            let file = file.unwrap();
+            let original = read_to_string(file.path()).unwrap();
+
+            let tree = SyntaxTree::from(original.as_str());
+            assert_eq!(original, tree.to_string());
+
+            let tree = SyntaxTree::from_path(file.path()).unwrap();
+            assert_eq!(original, tree.to_string());
+            assert_eq!(tree.to_srcinfo().unwrap(), original.parse().unwrap());
+        }
+    }
+
+    #[test]
+    fn test_parse_tree() {
+        let input = "pkgbase = a\n\tpkgver = 1\n\tpkgrel = 1\n\tfoo = b = c\npkgname = a\n";
+        let tree = SyntaxTree::from(input);
+        let srcinfo = tree.to_srcinfo().unwrap();
+
+        let line = &tree.lines()[3];
+        let extra = srcinfo.extra().get(line.key().unwrap()).unwrap();
+        assert_eq!(extra.all().collect::<Vec<_>>(), vec![line.value().unwrap()]);
+        assert_eq!(srcinfo, input.parse().unwrap());
+
+        let mut options = ParseOptions::new();
+        options.strict = true;
+        let err = options.parse_tree(&tree).unwrap_err();
+        assert!(matches!(err.kind, crate::ErrorKind::UnknownKey(_)));
+        assert_eq!(err.line.unwrap().number, 4);
+    }
+
+    #[test]
+    fn test_line_endings() {
+        let input = "pkgbase = a\r\n\tpkgver = 1\n\n  # foo  \r\npkgname = a";
+        let tree = SyntaxTree::from(input);
+
+        assert_eq!(tree.lines().len(), 5);
+        assert_eq!(tree.lines()[0].ending(), LineEnding::CrLf);
+        assert_eq!(tree.lines()[1].ending(), LineEnding::Lf);
+        assert_eq!(tree.lines()[4].ending(), LineEnding::None);
+        assert_eq!(tree.to_string(), input);
+    }
+
+    #[test]
+    fn test_line_kind() {
+        assert_eq!(LineKind::new(""), LineKind::Blank);
+        assert_eq!(LineKind::new(" \t "), LineKind::Blank);
+        assert_eq!(LineKind::new("  # foo"), LineKind::Comment(" foo"));
+        assert_eq!(
+            LineKind::new("\ta = b = c "),
+            LineKind::Field {
+                key: "a",
+                value: "b = c"
+            }
+        );
+        assert_eq!(
+            LineKind::new("a ="),
+            LineKind::Field {
+                key: "a",
+                value: ""
+            }
+        );
+        assert_eq!(LineKind::new(" a "), LineKind::Invalid("a"));
+    }
+
+    #[test]
+    fn test_set_value() {
+        let mut line = Line::new("\tpkgver  =  1.0 ");
+        assert!(line.set_value("2.0"));
+        assert_eq!(line.text(), "\tpkgver  =  2.0 ");
+
+        let mut line = Line::new("\tdepends =");
+        assert!(line.set_value("foo"));
+        assert_eq!(line.text(), "\tdepends = foo");
+
+        let mut line = Line::new("\tdepends = foo");
+        assert!(line.set_value(""));
+        assert_eq!(line.text(), "\tdepends = ");
+
+        let mut line = Line::new("# a = b");
+        assert!(!line.set_value("c"));
+        assert_eq!(line.text(), "# a = b");
+    }
+
+    #[test]
+    fn test_edit() {
+        let input = include_str!("../tests/srcinfo/libc++");
+        let mut tree = SyntaxTree::from(input);
+
+        assert_eq!(tree.section(None), Some(0..35));
+        assert_eq!(tree.section(Some("libc++abi")), Some(39..42));
+        assert_eq!(tree.section(Some("nope")), None);
+
+        assert!(tree.insert_field(Some("libc++abi"), "depends", "foo"));
+        assert_eq!(tree.remove_field(None, "validpgpkeys"), 2);
+        tree.field_mut(Some("libc++"), "pkgdesc")
+            .unwrap()
+            .set_value("bar");
+
+        let srcinfo = tree.to_srcinfo().unwrap();
+        assert_eq!(srcinfo.valid_pgp_keys(), &[] as &[String]);
+        assert_eq!(srcinfo.pkg("libc++").unwrap().pkgdesc(), Some("bar"));
+        assert_eq!(
+            srcinfo
+                .pkg("libc++abi")
+                .unwrap()
+                .depends()
+                .all()
+                .collect::<Vec<_>>(),
+            vec!["foo"]
+        );
+
+        let output = tree.to_string();
+        assert!(output.contains("\n\tdepends = foo\n"));
+        assert_eq!(output.lines().count(), input.lines().count() - 1);
+    }
+}
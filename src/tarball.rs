@@ -0,0 +1,172 @@
+use std::fs::File;
+use std::io::Read;
+use std::path::{Component, Path};
+
+use flate2::read::GzDecoder;
+use tar::{Archive, EntryType};
+
+use crate::error::{Error, ErrorKind};
+use crate::options::ParseOptions;
+use crate::srcinfo::Srcinfo;
+
+// matches ".SRCINFO" and "pkgbase/.SRCINFO" but not .SRCINFO files deeper in the archive,
+// a leading "./" as written by `tar -C . ...` is ignored
+fn is_srcinfo(path: &Path) -> bool {
+    let depth = path
+        .components()
+        .filter(|c| *c != Component::CurDir)
+        .count();
+    path.file_name().is_some_and(|name| name == ".SRCINFO") && depth <= 2
+}
+
+impl ParseOptions {
+    /// Parse the .SRCINFO inside of a `.tar.gz` archive.
+    ///
+    /// Requires the `tarball` feature.
+    pub fn parse_tarball<P: AsRef<Path>>(&self, path: P) -> Result<Srcinfo, Error> {
+        let mut archive = Archive::new(GzDecoder::new(File::open(path)?));
+        let mut found: Option<(String, Vec<u8>)> = None;
+
+        for entry in archive.entries()? {
+            let mut entry = entry?;
+            let path = entry.path()?;
+            if entry.header().entry_type() != EntryType::Regular || !is_srcinfo(&path) {
+                continue;
+            }
+
+            let path = path.to_string_lossy().into_owned();
+            if let Some((first, _)) = found {
+                return Err(ErrorKind::MultipleSrcinfo(first, path).into());
+            }
+
+            let mut bytes = Vec::new();
+            entry.read_to_end(&mut bytes)?;
+            found = Some((path, bytes));
+        }
+
+        match found {
+            Some((_, bytes)) => self.parse_bytes(&bytes),
+            None => Err(ErrorKind::MissingSrcinfo.into()),
+        }
+    }
+}
+
+impl Srcinfo {
+    /// Parse the .SRCINFO inside of a `.tar.gz` archive such as an AUR snapshot.
+    ///
+    /// The .SRCINFO may be at the top of the archive or inside of a single directory, usually
+    /// named after the pkgbase. .SRCINFO files nested deeper, such as ones belonging to bundled
+    /// sources, are ignored.
+    ///
+    /// Fails with [`ErrorKind::MissingSrcinfo`] if there is no .SRCINFO and
+    /// [`ErrorKind::MultipleSrcinfo`] if there is more than one.
+    ///
+    /// Requires the `tarball` feature.
+    ///
+    /// ```no_run
+    /// # use srcinfo::Error;
+    /// use srcinfo::Srcinfo;
+    ///
+    /// # fn test() -> Result<(), Error> {
+    /// let srcinfo = Srcinfo::from_tarball("example.tar.gz")?;
+    /// println!("{}", srcinfo.version());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn from_tarball<P: AsRef<Path>>(path: P) -> Result<Srcinfo, Error> {
+        ParseOptions::default().parse_tarball(path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{LIBCPP, TempPath};
+    use flate2::Compression;
+    use flate2::write::GzEncoder;
+    use std::fs;
+    use tar::{Builder, Header};
+
+    fn tarball(name: &str, files: &[(&str, &str)]) -> TempPath {
+        let path = TempPath::new(name);
+        let mut builder = Builder::new(GzEncoder::new(Vec::new(), Compression::default()));
+
+        for (name, content) in files {
+            // the name is written as is, set_path would drop a leading "./"
+            let mut header = Header::new_gnu();
+            header.as_old_mut().name[..name.len()].copy_from_slice(name.as_bytes());
+            header.set_size(content.len() as u64);
+            header.set_mode(0o644);
+            header.set_cksum();
+            builder.append(&header, content.as_bytes()).unwrap();
+        }
+
+        let data = builder.into_inner().unwrap().finish().unwrap();
+        fs::write(&path, data).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_from_tarball() {
+        let input = LIBCPP;
+        let path = tarball(
+            "tarball",
+            &[
+                ("libc++/PKGBUILD", ""),
+                ("libc++/.SRCINFO", input),
+                ("libc++/src/vendored/.SRCINFO", "pkgbase = foo"),
+            ],
+        );
+        let srcinfo = Srcinfo::from_tarball(&path).unwrap();
+        assert_eq!(srcinfo, input.parse().unwrap());
+    }
+
+    #[test]
+    fn test_from_tarball_cur_dir() {
+        let input = LIBCPP;
+        let path = tarball(
+            "tarball-cur-dir",
+            &[
+                ("./libc++/PKGBUILD", ""),
+                ("./libc++/.SRCINFO", input),
+                ("./libc++/src/vendored/.SRCINFO", "pkgbase = foo"),
+            ],
+        );
+        let srcinfo = Srcinfo::from_tarball(&path).unwrap();
+        assert_eq!(srcinfo, input.parse().unwrap());
+    }
+
+    #[test]
+    fn test_from_tarball_missing() {
+        let path = tarball("tarball-missing", &[("libc++/PKGBUILD", "")]);
+        let err = Srcinfo::from_tarball(&path).unwrap_err();
+
+        match err.kind {
+            ErrorKind::MissingSrcinfo => {}
+            _ => panic!("{:?}", err),
+        }
+    }
+
+    #[test]
+    fn test_from_tarball_multiple() {
+        let path = tarball(
+            "tarball-multiple",
+            &[("a/.SRCINFO", LIBCPP), ("b/.SRCINFO", LIBCPP)],
+        );
+        let err = Srcinfo::from_tarball(&path).unwrap_err();
+
+        match err.kind {
+            ErrorKind::MultipleSrcinfo(ref a, ref b) => {
+                assert_eq!(a, "a/.SRCINFO");
+                assert_eq!(b, "b/.SRCINFO");
+            }
+            _ => panic!("{:?}", err),
+        }
+    }
+
+    #[test]
+    fn test_from_tarball_io_error() {
+        let err = Srcinfo::from_tarball("").unwrap_err();
+        assert!(matches!(err.kind, ErrorKind::IoError(_)));
+    }
+}
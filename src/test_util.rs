@@ -0,0 +1,56 @@
+// helpers shared by the tests of the optional features that read from the file system
+// not every helper is used by every combination of features
+#![allow(dead_code)]
+
+use std::env;
+use std::fs;
+use std::ops::Deref;
+use std::path::{Path, PathBuf};
+use std::process;
+
+// a valid .SRCINFO to write into fixtures
+pub const LIBCPP: &str = include_str!("../tests/srcinfo/libc++");
+
+// a file or directory in the temp directory that is removed when dropped
+pub struct TempPath(PathBuf);
+
+impl TempPath {
+    // the name must be unique to the test as tests run in parallel
+    pub fn new(name: &str) -> TempPath {
+        let path = env::temp_dir().join(format!("srcinfo-test-{}-{}", name, process::id()));
+        remove(&path);
+        TempPath(path)
+    }
+
+    // writes a file below this directory, creating any missing parent directories
+    pub fn write<C: AsRef<[u8]>>(&self, file: &str, contents: C) {
+        let path = self.0.join(file);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, contents).unwrap();
+    }
+}
+
+fn remove(path: &Path) {
+    let _ = fs::remove_dir_all(path);
+    let _ = fs::remove_file(path);
+}
+
+impl Deref for TempPath {
+    type Target = Path;
+
+    fn deref(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl AsRef<Path> for TempPath {
+    fn as_ref(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl Drop for TempPath {
+    fn drop(&mut self) {
+        remove(&self.0);
+    }
+}
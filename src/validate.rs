@@ -0,0 +1,406 @@
+use crate::archvec::ArchVecs;
+use crate::error::{Error, ErrorKind};
+use crate::parse::key_arch;
+use crate::provenance::Provenance;
+use crate::srcinfo::{Package, PackageBase, Srcinfo};
+
+const CHECKSUMS: [&str; 8] = [
+    "cksums",
+    "md5sums",
+    "sha1sums",
+    "sha224sums",
+    "sha256sums",
+    "sha384sums",
+    "sha512sums",
+    "b2sums",
+];
+
+fn checksums(base: &PackageBase) -> [(&'static str, &ArchVecs); 8] {
+    [
+        (CHECKSUMS[0], &base.cksums),
+        (CHECKSUMS[1], &base.md5sums),
+        (CHECKSUMS[2], &base.sha1sums),
+        (CHECKSUMS[3], &base.sha224sums),
+        (CHECKSUMS[4], &base.sha256sums),
+        (CHECKSUMS[5], &base.sha384sums),
+        (CHECKSUMS[6], &base.sha512sums),
+        (CHECKSUMS[7], &base.b2sums),
+    ]
+}
+
+fn base_arch_vecs(base: &PackageBase) -> impl Iterator<Item = (&str, &ArchVecs)> {
+    [
+        ("source", &base.source),
+        ("makedepends", &base.makedepends),
+        ("checkdepends", &base.checkdepends),
+    ]
+    .into_iter()
+    .chain(checksums(base))
+    .chain(base.extra.iter().map(|(k, v)| (k.as_str(), v)))
+}
+
+fn pkg_arch_vecs(pkg: &Package) -> impl Iterator<Item = (&str, &ArchVecs)> {
+    [
+        ("depends", &pkg.depends),
+        ("optdepends", &pkg.optdepends),
+        ("provides", &pkg.provides),
+        ("conflicts", &pkg.conflicts),
+        ("replaces", &pkg.replaces),
+    ]
+    .into_iter()
+    .chain(pkg.extra.iter().map(|(k, v)| (k.as_str(), v)))
+}
+
+// the same rule as the parser: the _arch suffix of a field must be declared and must not be any
+fn check_arch<'a>(
+    errors: &mut Vec<Error>,
+    arches: &[String],
+    fields: impl Iterator<Item = (&'a str, &'a ArchVecs)>,
+    inherited: Option<(&Package, &Package)>,
+) {
+    for (key, vecs) in fields {
+        for vec in vecs {
+            let Some(arch) = vec.arch() else { continue };
+
+            // values inherited from the pkgbase have already been checked
+            let inherited = inherited
+                .filter(|(pkg, _)| pkg.provenance(key, Some(arch)) == Provenance::Inherited)
+                .and_then(|(_, base)| pkg_arch_vecs(base).find(|(k, _)| *k == key))
+                .and_then(|(_, base)| base.get(Some(arch)));
+            if inherited == Some(vec) {
+                continue;
+            }
+
+            if arch == "any" || !arches.iter().any(|a| a == arch) {
+                let kind = ErrorKind::UndeclaredArch(key_arch(key, Some(arch)), arch.to_string());
+                errors.push(kind.into());
+            }
+        }
+    }
+}
+
+impl PackageBase {
+    /// Checks that the checksums line up with the sources.
+    ///
+    /// Like makepkg, each checksum field must have as many values as the source field of the
+    /// same architecture. E.g. `sha256sums_x86_64` must have as many values as
+    /// `source_x86_64`. Every mismatch is returned as an [`ErrorKind::ChecksumCount`] holding
+    /// the algorithm and architecture.
+    ///
+    /// Each source field must also be covered by at least one checksum field of the same
+    /// architecture, otherwise [`ErrorKind::MissingChecksums`] is returned.
+    ///
+    /// ```
+    /// # use srcinfo::Error;
+    /// use srcinfo::{ErrorKind, Srcinfo};
+    ///
+    /// # fn test() -> Result<(), Error> {
+    /// let srcinfo: Srcinfo = "
+    /// pkgbase = example
+    /// pkgver = 1.5.0
+    /// pkgrel = 5
+    /// arch = x86_64
+    /// source = example.tar.gz
+    /// source_x86_64 = example-x86_64.bin
+    /// source_x86_64 = example-x86_64.sig
+    /// sha256sums = SKIP
+    /// sha256sums_x86_64 = SKIP
+    ///
+    /// pkgname = example".parse()?;
+    ///
+    /// let errors = srcinfo.base.check_checksums().unwrap_err();
+    /// match &errors[0].kind {
+    ///     ErrorKind::ChecksumCount(algorithm, arch, sources, checksums) => {
+    ///         assert_eq!(algorithm, "sha256sums");
+    ///         assert_eq!(arch.as_deref(), Some("x86_64"));
+    ///         assert_eq!((*sources, *checksums), (2, 1));
+    ///     }
+    ///     _ => panic!(),
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn check_checksums(&self) -> Result<(), Vec<Error>> {
+        let mut errors = Vec::new();
+
+        for (key, sums) in checksums(self) {
+            for vec in sums {
+                let arch = vec.arch();
+                let expected = self.source.get(arch).map_or(0, |v| v.values().len());
+                let found = vec.values().len();
+                if expected != found {
+                    let arch = arch.map(str::to_string);
+                    let kind = ErrorKind::ChecksumCount(key.to_string(), arch, expected, found);
+                    errors.push(kind.into());
+                }
+            }
+        }
+
+        for source in &self.source {
+            let arch = source.arch();
+            if !checksums(self)
+                .iter()
+                .any(|(_, sums)| sums.get(arch).is_some())
+            {
+                let kind = ErrorKind::MissingChecksums(arch.map(str::to_string));
+                errors.push(kind.into());
+            }
+        }
+
+        into_result(errors)
+    }
+}
+
+impl Srcinfo {
+    /// Checks that the Srcinfo is valid, returning every problem found.
+    ///
+    /// The parser already rejects most invalid input but a Srcinfo that was built or modified
+    /// by hand is never checked. This runs the same checks as the parser along with checks
+    /// that span the whole file:
+    ///
+    /// - pkgbase, pkgver, pkgrel and at least one pkgname must be set
+    ///   ([`ErrorKind::MissingField`]).
+    /// - The same pkgname can not be used twice ([`ErrorKind::DuplicatePkgname`]).
+    /// - The architecture of every architecture specific field must be declared by the
+    ///   section it is in ([`ErrorKind::UndeclaredArch`]).
+    /// - The arch of each package must be a subset of the pkgbase arch. A package may always
+    ///   use `any` and when the pkgbase uses `any` packages may use any architecture
+    ///   ([`ErrorKind::UndeclaredPackageArch`]).
+    /// - The checksums must line up with the sources, see [`PackageBase::check_checksums`].
+    ///
+    /// The returned errors do not have a line.
+    ///
+    /// ```
+    /// # use srcinfo::Error;
+    /// use srcinfo::{ArchVec, ErrorKind, Srcinfo};
+    ///
+    /// # fn test() -> Result<(), Error> {
+    /// let mut srcinfo: Srcinfo = "
+    /// pkgbase = example
+    /// pkgver = 1.5.0
+    /// pkgrel = 5
+    /// arch = x86_64
+    /// source = example.tar.gz
+    /// sha256sums = SKIP
+    ///
+    /// pkgname = example".parse()?;
+    ///
+    /// assert!(srcinfo.validate().is_ok());
+    ///
+    /// srcinfo.base.pkgver.clear();
+    /// srcinfo.pkgs[0].arch.push("aarch64".to_string());
+    ///
+    /// let errors = srcinfo.validate().unwrap_err();
+    /// assert_eq!(errors.len(), 2);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn validate(&self) -> Result<(), Vec<Error>> {
+        let mut errors = Vec::new();
+        self.structure_errors(&mut errors);
+
+        let arches = self.arch();
+        for pkg in &self.pkgs {
+            for arch in &pkg.arch {
+                if arch != "any" && !arches.contains(arch) && !arches.iter().any(|a| a == "any") {
+                    let kind = ErrorKind::UndeclaredPackageArch(pkg.pkgname.clone(), arch.clone());
+                    errors.push(kind.into());
+                }
+            }
+        }
+
+        if let Err(e) = self.base.check_checksums() {
+            errors.extend(e);
+        }
+
+        into_result(errors)
+    }
+
+    // the checks the parser always performs along with duplicate pkgnames, which the parser
+    // only rejects in strict mode, used by SrcinfoBuilder::build
+    pub(crate) fn check_built(&self) -> Result<(), Vec<Error>> {
+        let mut errors = Vec::new();
+        self.structure_errors(&mut errors);
+        into_result(errors)
+    }
+
+    fn structure_errors(&self, errors: &mut Vec<Error>) {
+        for (field, missing) in [
+            ("pkgbase", self.pkgbase().is_empty()),
+            ("pkgname", self.pkgs.is_empty()),
+            ("pkgver", self.pkgver().is_empty()),
+            ("pkgrel", self.pkgrel().is_empty()),
+        ] {
+            if missing {
+                errors.push(ErrorKind::MissingField(field.to_string()).into());
+            }
+        }
+
+        for (i, pkg) in self.pkgs.iter().enumerate() {
+            if self.pkgs[..i].iter().any(|p| p.pkgname == pkg.pkgname) {
+                errors.push(ErrorKind::DuplicatePkgname(pkg.pkgname.clone()).into());
+            }
+        }
+
+        let arches = self.arch();
+        check_arch(errors, arches, base_arch_vecs(&self.base), None);
+        check_arch(errors, arches, pkg_arch_vecs(&self.pkg), None);
+
+        for pkg in &self.pkgs {
+            let pkg_arches = if pkg.arch.is_empty() {
+                arches
+            } else {
+                &pkg.arch
+            };
+            check_arch(
+                errors,
+                pkg_arches,
+                pkg_arch_vecs(pkg),
+                Some((pkg, &self.pkg)),
+            );
+        }
+    }
+}
+
+fn into_result(errors: Vec<Error>) -> Result<(), Vec<Error>> {
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::archvec::ArchVec;
+    use std::fs;
+
+    #[test]
+    fn test_validate_good() {
+        for file in fs::read_dir("tests/srcinfo/good").unwrap() {
+            let path = file.unwrap().path();
+            let srcinfo = Srcinfo::from_path(&path).unwrap();
+            assert!(
+                srcinfo.validate().is_ok(),
+                "{:?} {:?}",
+                path,
+                srcinfo.validate()
+            );
+        }
+    }
+
+    #[test]
+    fn test_validate() {
+        let mut srcinfo = include_str!("../tests/srcinfo/libc++")
+            .parse::<Srcinfo>()
+            .unwrap();
+        assert!(srcinfo.validate().is_ok());
+
+        srcinfo.base.pkgrel.clear();
+        srcinfo.pkgs[2].pkgname = "libc++".to_string();
+        srcinfo.pkgs[1].arch.push("aarch64".to_string());
+        srcinfo.pkgs[1].arch.push("any".to_string());
+        srcinfo.pkgs[0]
+            .depends
+            .vecs
+            .push(ArchVec::with_values(Some("any"), vec!["foo".to_string()]));
+        srcinfo.base.sha512sums.vecs.clear();
+        srcinfo.base.md5sums.vecs.push(ArchVec::with_values(
+            None::<String>,
+            vec!["SKIP".to_string()],
+        ));
+
+        let errors = srcinfo
+            .validate()
+            .unwrap_err()
+            .into_iter()
+            .map(|e| e.to_string())
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            errors,
+            vec![
+                "field 'pkgrel' is required",
+                "pkgname 'libc++' already declared",
+                "undeclared architecture 'any' in key 'depends_any'",
+                "package 'libc++abi' uses architecture 'aarch64' which is not declared by the pkgbase",
+                "key 'md5sums' has 1 values, expected 6",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_checksum_counts() {
+        let input = include_str!("../tests/srcinfo/cksums");
+        let mut srcinfo = input.parse::<Srcinfo>().unwrap();
+        assert!(srcinfo.validate().is_ok());
+
+        srcinfo.base.source.vecs[1]
+            .values
+            .push("extra.patch".to_string());
+        srcinfo.base.sha256sums.vecs.remove(2);
+
+        let errors = srcinfo
+            .validate()
+            .unwrap_err()
+            .into_iter()
+            .map(|e| e.to_string())
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            errors,
+            vec![
+                "key 'cksums_x86_64' has 1 values, expected 2",
+                "key 'sha256sums_x86_64' has 1 values, expected 2",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_check_checksums() {
+        let mut srcinfo = include_str!("../tests/srcinfo/cksums")
+            .parse::<Srcinfo>()
+            .unwrap();
+        assert!(srcinfo.base.check_checksums().is_ok());
+
+        srcinfo.base.source.vecs[2]
+            .values
+            .push("extra.patch".to_string());
+        let errors = srcinfo.base.check_checksums().unwrap_err();
+        let errors = errors.iter().map(|e| &e.kind).collect::<Vec<_>>();
+
+        assert_eq!(errors.len(), 2);
+        for (err, algorithm) in errors.into_iter().zip(["cksums", "sha256sums"]) {
+            match err {
+                ErrorKind::ChecksumCount(key, arch, sources, checksums) => {
+                    assert_eq!(key, algorithm);
+                    assert_eq!(arch.as_deref(), Some("aarch64"));
+                    assert_eq!((*sources, *checksums), (2, 1));
+                }
+                _ => panic!("{:?}", err),
+            }
+        }
+
+        srcinfo.base.cksums.vecs.clear();
+        srcinfo.base.sha256sums.vecs.clear();
+        let errors = srcinfo
+            .base
+            .check_checksums()
+            .unwrap_err()
+            .into_iter()
+            .map(|e| e.to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            errors,
+            vec![
+                "key 'source' has no checksums",
+                "key 'source_x86_64' has no checksums",
+                "key 'source_aarch64' has no checksums",
+            ]
+        );
+
+        srcinfo.base.source.vecs.clear();
+        assert!(srcinfo.base.check_checksums().is_ok());
+    }
+}
@@ -0,0 +1,387 @@
+use std::cmp::Ordering;
+use std::str::FromStr;
+
+use crate::error::{Error, ErrorKind};
+use crate::srcinfo::Srcinfo;
+
+// splits "epoch:pkgver-pkgrel" the same way as pacman's parseEVR
+fn split_version(s: &str) -> (Option<&str>, &str, Option<&str>) {
+    let digits = s.bytes().take_while(u8::is_ascii_digit).count();
+    let (epoch, rest) = match s[digits..].strip_prefix(':') {
+        Some(rest) => (Some(&s[..digits]), rest),
+        None => (None, s),
+    };
+
+    match rest.rsplit_once('-') {
+        Some((pkgver, pkgrel)) => (epoch, pkgver, Some(pkgrel)),
+        None => (epoch, rest, None),
+    }
+}
+
+// a port of rpmvercmp from pacman's libalpm/version.c
+fn rpmvercmp(a: &str, b: &str) -> Ordering {
+    if a == b {
+        return Ordering::Equal;
+    }
+
+    let (a, b) = (a.as_bytes(), b.as_bytes());
+    let (mut one, mut two) = (0, 0);
+
+    while one < a.len() && two < b.len() {
+        let (sep1, sep2) = (one, two);
+        while one < a.len() && !a[one].is_ascii_alphanumeric() {
+            one += 1;
+        }
+        while two < b.len() && !b[two].is_ascii_alphanumeric() {
+            two += 1;
+        }
+
+        if one == a.len() || two == b.len() {
+            break;
+        }
+
+        // if the separator lengths were different, we are finished
+        if one - sep1 != two - sep2 {
+            return (one - sep1).cmp(&(two - sep2));
+        }
+
+        // grab the first completely alpha or completely numeric segment
+        let isnum = a[one].is_ascii_digit();
+        let segment = |s: &[u8], start: usize| -> usize {
+            let len = if isnum {
+                s[start..].iter().take_while(|c| c.is_ascii_digit()).count()
+            } else {
+                s[start..]
+                    .iter()
+                    .take_while(|c| c.is_ascii_alphabetic())
+                    .count()
+            };
+            start + len
+        };
+        let (end1, end2) = (segment(a, one), segment(b, two));
+
+        // numeric segments are always newer than alpha segments
+        if end2 == two {
+            return if isnum {
+                Ordering::Greater
+            } else {
+                Ordering::Less
+            };
+        }
+
+        let (mut seg1, mut seg2) = (&a[one..end1], &b[two..end2]);
+        if isnum {
+            // throw away any leading zeros, whichever number has more digits wins
+            while let [b'0', rest @ ..] = seg1 {
+                seg1 = rest;
+            }
+            while let [b'0', rest @ ..] = seg2 {
+                seg2 = rest;
+            }
+
+            match seg1.len().cmp(&seg2.len()) {
+                Ordering::Equal => (),
+                ord => return ord,
+            }
+        }
+
+        match seg1.cmp(seg2) {
+            Ordering::Equal => (),
+            ord => return ord,
+        }
+
+        one = end1;
+        two = end2;
+    }
+
+    match (a.get(one), b.get(two)) {
+        // all segments compared identically but the separators were different
+        (None, None) => Ordering::Equal,
+        // a remaining alpha string never beats an empty string
+        (None, Some(c)) if !c.is_ascii_alphabetic() => Ordering::Less,
+        (Some(c), _) if c.is_ascii_alphabetic() => Ordering::Less,
+        _ => Ordering::Greater,
+    }
+}
+
+/// Compares two version strings the same way as pacman's `vercmp`.
+///
+/// The pkgrel is only compared when both versions have one. A missing epoch is treated
+/// as 0.
+///
+/// ```
+/// use std::cmp::Ordering;
+/// use srcinfo::vercmp;
+///
+/// assert_eq!(vercmp("1.0rc", "1.0"), Ordering::Less);
+/// assert_eq!(vercmp("1:1.0", "2.0"), Ordering::Greater);
+/// assert_eq!(vercmp("1.0-1", "1.0"), Ordering::Equal);
+/// ```
+pub fn vercmp(a: &str, b: &str) -> Ordering {
+    if a == b {
+        return Ordering::Equal;
+    }
+
+    cmp_evr(split_version(a), split_version(b))
+}
+
+// a missing or empty epoch is 0
+fn epoch(epoch: Option<&str>) -> &str {
+    epoch.filter(|e| !e.is_empty()).unwrap_or("0")
+}
+
+fn cmp_evr(
+    (epoch1, ver1, rel1): (Option<&str>, &str, Option<&str>),
+    (epoch2, ver2, rel2): (Option<&str>, &str, Option<&str>),
+) -> Ordering {
+    rpmvercmp(epoch(epoch1), epoch(epoch2))
+        .then_with(|| rpmvercmp(ver1, ver2))
+        .then_with(|| match (rel1, rel2) {
+            (Some(rel1), Some(rel2)) => rpmvercmp(rel1, rel2),
+            _ => Ordering::Equal,
+        })
+}
+
+/// A package version in the format "epoch:pkgver-pkgrel".
+///
+/// [`Version::vercmp`] compares versions exactly like pacman's [`vercmp`], where a version
+/// without a pkgrel matches the same pkgver with any pkgrel. That is not a total order so it
+/// is not what [`Ord`] uses: Ord compares the epoch, pkgver and pkgrel like vercmp but puts a
+/// version without a pkgrel before the same version with one, and falls back to comparing
+/// the strings so that only versions that are written the same way are equal. So "1.0" sorts
+/// before "1.0-1" and "1.0" is not equal to "1.00".
+///
+/// ```
+/// # use srcinfo::Error;
+/// use srcinfo::Version;
+///
+/// # fn test() -> Result<(), Error> {
+/// let version: Version = "1:2.0.1-3".parse()?;
+/// assert_eq!(version.epoch.as_deref(), Some("1"));
+/// assert_eq!(version.pkgver, "2.0.1");
+/// assert_eq!(version.pkgrel.as_deref(), Some("3"));
+/// assert!(version > "2.1-1".parse()?);
+/// assert_eq!(version.to_string(), "1:2.0.1-3");
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Version {
+    /// The epoch, treated as 0 when not set
+    pub epoch: Option<String>,
+    /// The pkgver
+    pub pkgver: String,
+    /// The pkgrel
+    pub pkgrel: Option<String>,
+}
+
+impl Version {
+    /// Creates a new Version.
+    pub fn new<S: Into<String>>(epoch: Option<S>, pkgver: S, pkgrel: Option<S>) -> Version {
+        Version {
+            epoch: epoch.map(Into::into),
+            pkgver: pkgver.into(),
+            pkgrel: pkgrel.map(Into::into),
+        }
+    }
+
+    /// Compares two versions the same way as pacman's [`vercmp`].
+    ///
+    /// Unlike [`Ord`], a version without a pkgrel is equal to the same version with any
+    /// pkgrel. This is what [`Dependency`](crate::Dependency) uses to match versions.
+    pub fn vercmp(&self, other: &Version) -> Ordering {
+        cmp_evr(self.evr(), other.evr())
+    }
+
+    fn evr(&self) -> (Option<&str>, &str, Option<&str>) {
+        (self.epoch.as_deref(), &self.pkgver, self.pkgrel.as_deref())
+    }
+}
+
+impl FromStr for Version {
+    type Err = Error;
+
+    /// Parses a version in the format "epoch:pkgver-pkgrel".
+    ///
+    /// The epoch and pkgrel are optional. Returns [`ErrorKind::InvalidVersion`] if the
+    /// pkgver is empty.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match split_version(s) {
+            (_, "", _) => Err(ErrorKind::InvalidVersion(s.to_string()).into()),
+            (epoch, pkgver, pkgrel) => Ok(Version::new(epoch, pkgver, pkgrel)),
+        }
+    }
+}
+
+impl PartialEq for Version {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Version {}
+
+impl PartialOrd for Version {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Version {
+    fn cmp(&self, other: &Self) -> Ordering {
+        rpmvercmp(epoch(self.epoch.as_deref()), epoch(other.epoch.as_deref()))
+            .then_with(|| rpmvercmp(&self.pkgver, &other.pkgver))
+            .then_with(|| match (&self.pkgrel, &other.pkgrel) {
+                (Some(rel1), Some(rel2)) => rpmvercmp(rel1, rel2),
+                (rel1, rel2) => rel1.is_some().cmp(&rel2.is_some()),
+            })
+            .then_with(|| self.evr().cmp(&other.evr()))
+    }
+}
+
+impl Srcinfo {
+    /// Returns the version of the pkgbase as a [`Version`].
+    pub fn typed_version(&self) -> Version {
+        Version {
+            epoch: self.base.epoch.clone(),
+            pkgver: self.base.pkgver.clone(),
+            pkgrel: Some(self.base.pkgrel.clone()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // the cases from pacman's test/util/vercmptest.sh
+    const VERCMP: &[(&str, &str, i8)] = &[
+        // all similar length, no pkgrel
+        ("1.5.0", "1.5.0", 0),
+        ("1.5.1", "1.5.0", 1),
+        // mixed length
+        ("1.5.1", "1.5", 1),
+        // with pkgrel, simple
+        ("1.5.0-1", "1.5.0-1", 0),
+        ("1.5.0-1", "1.5.0-2", -1),
+        ("1.5.0-1", "1.5.1-1", -1),
+        ("1.5.0-2", "1.5.1-1", -1),
+        // with pkgrel, mixed lengths
+        ("1.5-1", "1.5.1-1", -1),
+        ("1.5-2", "1.5.1-1", -1),
+        ("1.5-2", "1.5.1-2", -1),
+        // mixed pkgrel inclusion
+        ("1.5", "1.5-1", 0),
+        ("1.5-1", "1.5", 0),
+        ("1.1-1", "1.1", 0),
+        ("1.0-1", "1.1", -1),
+        ("1.1-1", "1.0", 1),
+        // alphanumeric versions
+        ("1.5b-1", "1.5-1", -1),
+        ("1.5b", "1.5", -1),
+        ("1.5b-1", "1.5", -1),
+        ("1.5b", "1.5.1", -1),
+        // from the manpage
+        ("1.0a", "1.0alpha", -1),
+        ("1.0alpha", "1.0b", -1),
+        ("1.0b", "1.0beta", -1),
+        ("1.0beta", "1.0rc", -1),
+        ("1.0rc", "1.0", -1),
+        // going crazy? alpha-dotted versions
+        ("1.5.a", "1.5", 1),
+        ("1.5.b", "1.5.a", 1),
+        ("1.5.1", "1.5.b", 1),
+        // alpha dots and dashes
+        ("1.5.b-1", "1.5.b", 0),
+        ("1.5-1", "1.5.b", -1),
+        // same/similar content, differing separators
+        ("2.0", "2_0", 0),
+        ("2.0_a", "2_0.a", 0),
+        ("2.0a", "2.0.a", -1),
+        ("2___a", "2_a", 1),
+        // epoch included version comparisons
+        ("0:1.0", "0:1.0", 0),
+        ("0:1.0", "0:1.1", -1),
+        ("1:1.0", "0:1.0", 1),
+        ("1:1.0", "0:1.1", 1),
+        ("1:1.0", "2:1.1", -1),
+        // epoch + sometimes present pkgrel
+        ("1:1.0", "0:1.0-1", 1),
+        ("1:1.0-1", "0:1.1-1", 1),
+        // epoch included on one version
+        ("0:1.0", "1.0", 0),
+        ("0:1.0", "1.1", -1),
+        ("0:1.1", "1.0", 1),
+        ("1:1.0", "1.0", 1),
+        ("1:1.0", "1.1", 1),
+        ("1:1.1", "1.1", 1),
+    ];
+
+    #[test]
+    fn test_vercmp() {
+        for &(a, b, expected) in VERCMP {
+            let expected = expected.cmp(&0);
+            assert_eq!(vercmp(a, b), expected, "{} {}", a, b);
+            assert_eq!(vercmp(b, a), expected.reverse(), "{} {}", b, a);
+
+            let (a, b) = (a.parse::<Version>().unwrap(), b.parse::<Version>().unwrap());
+            assert_eq!(a.vercmp(&b), expected, "{} {}", a, b);
+            assert_eq!(b.vercmp(&a), expected.reverse(), "{} {}", b, a);
+            if expected != Ordering::Equal {
+                assert_eq!(a.cmp(&b), expected, "{} {}", a, b);
+            }
+        }
+    }
+
+    #[test]
+    fn test_version_ord() {
+        let mut versions = ["1.0-2", "1.00", "1.0", "0:1.0-1", "1.0-1", "1.0", "0.9-3"]
+            .map(|s| s.parse::<Version>().unwrap())
+            .to_vec();
+        versions.sort();
+        versions.dedup();
+
+        let versions = versions.iter().map(|v| v.to_string()).collect::<Vec<_>>();
+        assert_eq!(
+            versions,
+            ["0.9-3", "1.0", "1.00", "1.0-1", "0:1.0-1", "1.0-2"]
+        );
+
+        let version = |s: &str| s.parse::<Version>().unwrap();
+        assert_ne!(version("1.0"), version("1.0-1"));
+        assert!(version("1.0") < version("1.0-1"));
+        assert_eq!(version("1.0").vercmp(&version("1.0-1")), Ordering::Equal);
+    }
+
+    #[test]
+    fn test_version_parse() {
+        for s in ["1.0", "1:1.0", "1.0-1", "2:1.0-1.1", "1.0-rc1-2"] {
+            assert_eq!(s.parse::<Version>().unwrap().to_string(), s);
+        }
+
+        let version = "1.0-rc1-2".parse::<Version>().unwrap();
+        assert_eq!(version.pkgver, "1.0-rc1");
+        assert_eq!(version.pkgrel.as_deref(), Some("2"));
+
+        let version = "a:1.0".parse::<Version>().unwrap();
+        assert_eq!(version.epoch, None);
+        assert_eq!(version.pkgver, "a:1.0");
+
+        for s in ["", "1:", "-1", "1:-1"] {
+            let err = s.parse::<Version>().unwrap_err();
+            assert!(matches!(err.kind, ErrorKind::InvalidVersion(v) if v == s));
+        }
+    }
+
+    #[test]
+    fn test_typed_version() {
+        let mut srcinfo = Srcinfo::from_path("tests/srcinfo/libc++").unwrap();
+        assert_eq!(srcinfo.typed_version().to_string(), srcinfo.version());
+
+        srcinfo.base.epoch = Some("1".to_string());
+        let version = srcinfo.typed_version();
+        assert_eq!(version.to_string(), srcinfo.version());
+        assert_eq!(version, Version::new(Some("1"), "6.0.0", Some("1")));
+    }
+}
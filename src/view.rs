@@ -0,0 +1,158 @@
+use std::collections::BTreeMap;
+
+use crate::archvec::ArchVecs;
+use crate::srcinfo::{Package, PackageBase, Srcinfo, full_version, get, geto};
+
+/// A [`Package`] together with the [`PackageBase`] it belongs to.
+///
+/// Gives access to the package fields as well as the fields that only the pkgbase can set,
+/// such as `makedepends` and `source`. Created with [`Srcinfo::pkgs_resolved`].
+///
+/// ```
+/// # use srcinfo::Error;
+/// use srcinfo::Srcinfo;
+///
+/// # fn test() -> Result<(), Error> {
+/// let srcinfo: Srcinfo = "
+/// pkgbase = example
+/// pkgver = 1.5.0
+/// pkgrel = 5
+/// arch = x86_64
+/// makedepends = cmake
+///
+/// pkgname = example
+///
+/// pkgname = example-docs
+/// arch = any".parse()?;
+///
+/// for pkg in srcinfo.pkgs_resolved() {
+///     println!("{} {}", pkg.pkgname(), pkg.version());
+///     println!("{}", pkg.file_name("x86_64", ".pkg.tar.zst"));
+///     for depend in pkg.makedepends().arch("x86_64") {
+///         println!("makedepend: {}", depend);
+///     }
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[non_exhaustive]
+pub struct PackageView<'a> {
+    /// The pkgbase of the package
+    pub base: &'a PackageBase,
+    /// The package
+    pub pkg: &'a Package,
+}
+
+impl<'a> PackageView<'a> {
+    /// Creates a new PackageView.
+    pub fn new(base: &'a PackageBase, pkg: &'a Package) -> PackageView<'a> {
+        PackageView { base, pkg }
+    }
+
+    /// Returns the full version of the package, "epoch:pkgver-pkgrel".
+    ///
+    /// The epoch is left out when not set.
+    pub fn version(&self) -> String {
+        full_version(self.epoch(), self.pkgver(), self.pkgrel())
+    }
+
+    /// Returns the file name makepkg gives the built package,
+    /// "pkgname-version-arch" followed by pkgext.
+    ///
+    /// Like makepkg the architecture is "any" when the package is built for any architecture.
+    /// pkgext is the value of `PKGEXT` such as ".pkg.tar.zst".
+    pub fn file_name(&self, arch: &str, pkgext: &str) -> String {
+        let arch = if self.arch().iter().any(|a| a == "any") {
+            "any"
+        } else {
+            arch
+        };
+        format!("{}-{}-{}{}", self.pkgname(), self.version(), arch, pkgext)
+    }
+
+    get!(pkgbase, base.pkgbase, &'a str);
+    get!(pkgver, base.pkgver, &'a str);
+    get!(pkgrel, base.pkgrel, &'a str);
+    geto!(epoch, base.epoch, Option<&'a str>);
+    get!(source, base.source, &'a ArchVecs);
+    get!(valid_pgp_keys, base.valid_pgp_keys, &'a [String]);
+    get!(no_extract, base.no_extract, &'a [String]);
+    get!(cksums, base.cksums, &'a ArchVecs);
+    get!(md5sums, base.md5sums, &'a ArchVecs);
+    get!(sha1sums, base.sha1sums, &'a ArchVecs);
+    get!(sha224sums, base.sha224sums, &'a ArchVecs);
+    get!(sha256sums, base.sha256sums, &'a ArchVecs);
+    get!(sha384sums, base.sha384sums, &'a ArchVecs);
+    get!(sha512sums, base.sha512sums, &'a ArchVecs);
+    get!(b2sums, base.b2sums, &'a ArchVecs);
+    get!(makedepends, base.makedepends, &'a ArchVecs);
+    get!(checkdepends, base.checkdepends, &'a ArchVecs);
+
+    get!(pkgname, pkg.pkgname, &'a str);
+    geto!(pkgdesc, pkg.pkgdesc, Option<&'a str>);
+    get!(arch, pkg.arch, &'a [String]);
+    geto!(url, pkg.url, Option<&'a str>);
+    get!(license, pkg.license, &'a [String]);
+    get!(groups, pkg.groups, &'a [String]);
+    get!(depends, pkg.depends, &'a ArchVecs);
+    get!(optdepends, pkg.optdepends, &'a ArchVecs);
+    get!(provides, pkg.provides, &'a ArchVecs);
+    get!(conflicts, pkg.conflicts, &'a ArchVecs);
+    get!(replaces, pkg.replaces, &'a ArchVecs);
+    get!(backup, pkg.backup, &'a [String]);
+    get!(options, pkg.options, &'a [String]);
+    geto!(install, pkg.install, Option<&'a str>);
+    geto!(changelog, pkg.changelog, Option<&'a str>);
+    get!(extra, pkg.extra, &'a BTreeMap<String, ArchVecs>);
+}
+
+impl Srcinfo {
+    /// Returns an iterator over the packages in this Srcinfo, each combined with the
+    /// pkgbase.
+    pub fn pkgs_resolved(&self) -> impl Iterator<Item = PackageView<'_>> {
+        self.pkgs
+            .iter()
+            .map(|pkg| PackageView::new(&self.base, pkg))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_package_view() {
+        let srcinfo = Srcinfo::from_path("tests/srcinfo/libc++").unwrap();
+        let pkgs = srcinfo.pkgs_resolved().collect::<Vec<_>>();
+
+        assert_eq!(pkgs.len(), 3);
+        assert_eq!(pkgs[1].pkgname(), "libc++abi");
+        assert_eq!(pkgs[1].pkgbase(), "libc++");
+        assert_eq!(pkgs[1].version(), srcinfo.version());
+        assert_eq!(pkgs[1].makedepends(), srcinfo.makedepends());
+        assert_eq!(pkgs[1].depends(), srcinfo.pkgs()[1].depends());
+        assert_eq!(
+            pkgs[1].file_name("x86_64", ".pkg.tar.zst"),
+            "libc++abi-6.0.0-1-x86_64.pkg.tar.zst"
+        );
+
+        let srcinfo: Srcinfo = "
+pkgbase = a
+pkgver = 1
+pkgrel = 2
+epoch = 3
+arch = x86_64
+
+pkgname = a-docs
+arch = any"
+            .parse()
+            .unwrap();
+        let pkg = srcinfo.pkgs_resolved().next().unwrap();
+        assert_eq!(pkg.version(), "3:1-2");
+        assert_eq!(
+            pkg.file_name("x86_64", ".pkg.tar.xz"),
+            "a-docs-3:1-2-any.pkg.tar.xz"
+        );
+    }
+}